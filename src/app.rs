@@ -1,12 +1,24 @@
+use crate::say;
+use crate::version::{UpdatePolicy, VersionScheme};
 use regex::Regex;
 use semver::Version;
 /// Defines application information and its details.
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::env;
 use std::fmt;
-use std::process::Command;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
 /// Represents an application with its details.
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct App {
     pub name: String,
     pub bin: String,
@@ -14,7 +26,108 @@ pub struct App {
     pub repo: Option<String>,
     pub install_command: Option<String>,
     pub update_command: Option<String>,
+    pub uninstall_command: Option<String>,
     pub version_command: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bins: Vec<Bin>,
+    /// Also install man pages and shell completions shipped in the archive
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub extras: bool,
+    #[serde(default, skip_serializing_if = "Layout::is_binary")]
+    pub layout: Layout,
+    /// Number of installed versions kept for rollbacks (overrides the global setting)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_versions: Option<usize>,
+    /// Also run the binary without arguments to find its version in the help output
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub probe_without_args: bool,
+    /// Arguments printing the installed version, instead of trying the usual version flags
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_args: Option<Vec<String>>,
+    /// Regex extracting the installed version (first capture group, or the whole match)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_regex: Option<String>,
+    /// Prefix of the release tags of this app (e.g. `cli-v` for `cli-v2.3.1`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag_prefix: Option<String>,
+    /// Regex matching the release tags of this app and extracting their version
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag_regex: Option<String>,
+    /// How versions are parsed and ordered
+    #[serde(default, skip_serializing_if = "VersionScheme::is_semver")]
+    pub version_scheme: VersionScheme,
+    /// Which updates are applied (overrides the global setting)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update: Option<UpdatePolicy>,
+}
+
+/// Fields of an app entry in the configuration file, as accepted by `set`.
+pub const FIELDS: &[&str] = &[
+    "name",
+    "bin",
+    "description",
+    "repo",
+    "install_command",
+    "update_command",
+    "uninstall_command",
+    "version_command",
+    "bins",
+    "extras",
+    "layout",
+    "keep_versions",
+    "probe_without_args",
+    "version_args",
+    "version_regex",
+    "tag_prefix",
+    "tag_regex",
+    "version_scheme",
+    "update",
+];
+
+/// How a GitHub release is installed.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    /// Copy the binaries into `bin_dir`
+    #[default]
+    Binary,
+    /// Keep the whole extracted archive and symlink the binaries into `bin_dir`
+    Directory,
+}
+
+impl Layout {
+    pub fn is_binary(&self) -> bool {
+        *self == Layout::Binary
+    }
+}
+
+/// A binary to install from a release archive.
+///
+/// Either a plain name, or a `from`/`to` pair to rename the file found in the
+/// archive when installing it.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(untagged)]
+pub enum Bin {
+    Name(String),
+    Renamed { from: String, to: String },
+}
+
+impl Bin {
+    /// Name of the binary in the release archive.
+    pub fn source(&self) -> &str {
+        match self {
+            Bin::Name(name) => name,
+            Bin::Renamed { from, .. } => from,
+        }
+    }
+
+    /// Name of the installed binary.
+    pub fn target(&self) -> &str {
+        match self {
+            Bin::Name(name) => name,
+            Bin::Renamed { to, .. } => to,
+        }
+    }
 }
 
 // app information
@@ -24,14 +137,30 @@ pub struct AppStatus {
     pub current_version: Option<String>,
     pub latest_version: Option<String>,
     pub pixi_managed: Option<bool>,
+    /// The binary installed in `bin_dir`, if any
+    pub managed_path: Option<PathBuf>,
+    /// The binary found first in `PATH`, if any
+    pub resolved_path: Option<PathBuf>,
+    /// A newer version not applied because of the update policy
+    pub held_back_version: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
 pub enum InstallationMethod {
     GitHub,   // Direct download from GitHub releases
     Commands, // Custom install/update commands
 }
 
+impl fmt::Display for InstallationMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InstallationMethod::GitHub => write!(f, "github"),
+            InstallationMethod::Commands => write!(f, "commands"),
+        }
+    }
+}
+
 impl fmt::Display for App {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -61,12 +190,24 @@ impl fmt::Display for AppStatus {
 
         match (&self.current_version, &self.latest_version) {
             (Some(current), Some(latest)) => {
-                if self.is_version_update_needed() {
+                if self.is_newer_than_latest() {
+                    write!(
+                        f,
+                        "⏫ {} v{} is newer than the latest release (v{})",
+                        self.app.name, current, latest
+                    )
+                } else if self.is_version_update_needed() {
                     write!(
                         f,
                         "🆕 {} v{} -> v{} (update available)",
                         self.app.name, current, latest
                     )
+                } else if self.held_back_version.is_some() {
+                    write!(
+                        f,
+                        "✅ {} is already at the latest allowed version ({})",
+                        self.app.name, current
+                    )
                 } else {
                     write!(
                         f,
@@ -92,7 +233,15 @@ impl fmt::Display for AppStatus {
             _ => {
                 write!(f, "❓ {} (version unknown)", self.app.name)
             }
+        }?;
+
+        if let Some(held_back) = &self.held_back_version {
+            write!(f, "\n   ⏸️  v{} held back by the update policy", held_back)?;
+        }
+        if let Some(warning) = self.path_warning() {
+            write!(f, "\n   ⚠️  {}", warning)?;
         }
+        Ok(())
     }
 }
 
@@ -105,6 +254,71 @@ impl App {
         self.repo.as_ref().map_or("", |v| v)
     }
 
+    /**
+     * Get the binaries to install for the app.
+     * Defaults to `bin` when no `bins` list is given.
+     */
+    pub fn binaries(&self) -> Vec<Bin> {
+        if self.bins.is_empty() {
+            vec![Bin::Name(self.bin.clone())]
+        } else {
+            self.bins.clone()
+        }
+    }
+
+    /**
+     * Get the update policy of the app, defaulting to the global setting.
+     */
+    pub fn update_policy(&self, settings: &crate::Settings) -> UpdatePolicy {
+        self.update.unwrap_or(settings.update)
+    }
+
+    /**
+     * Check whether only some release tags of the repository belong to the app,
+     * as in repositories publishing several products.
+     */
+    pub fn filters_tags(&self) -> bool {
+        self.tag_prefix.is_some() || self.tag_regex.is_some()
+    }
+
+    /**
+     * Get the version of the app from a release tag.
+     *
+     * With `tag_prefix`, the tag must start with the prefix, which is removed.
+     * With `tag_regex`, the rest must match the regex and the version is its
+     * first capture group (or the whole match). Otherwise the first version
+     * found in the rest is used.
+     *
+     * Returns None if the tag does not belong to the app or has no version
+     * (or if `tag_regex` is invalid, see `validate`).
+     */
+    pub fn version_from_tag(&self, tag: &str) -> Option<String> {
+        let rest = match &self.tag_prefix {
+            Some(prefix) => tag.strip_prefix(prefix.as_str())?,
+            None => tag,
+        };
+        match &self.tag_regex {
+            Some(pattern) => {
+                let regex = compiled_regex(pattern).ok()?;
+                extract_version_with_regex(rest, &regex, self.version_scheme)
+            }
+            None => self.version_scheme.extract(rest),
+        }
+    }
+
+    /**
+     * Check the settings of the app that cannot be checked when parsing it.
+     *
+     * Returns an error if `tag_regex` is not a valid regex.
+     */
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some(pattern) = &self.tag_regex {
+            compiled_regex(pattern)
+                .map_err(|e| anyhow::anyhow!("Invalid tag_regex for {}: {}", self.name, e))?;
+        }
+        Ok(())
+    }
+
     /**
      * Get the installation method for the app whether it is a command
      * or a github template
@@ -118,6 +332,57 @@ impl App {
     }
 }
 
+/// Regexes of the configuration, compiled once per run.
+static COMPILED_REGEXES: LazyLock<Mutex<HashMap<String, Regex>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Compile a regex of the configuration, or get it from the previous compilations.
+fn compiled_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    let mut regexes = COMPILED_REGEXES.lock().unwrap();
+    if let Some(regex) = regexes.get(pattern) {
+        return Ok(regex.clone());
+    }
+    let regex = Regex::new(pattern)?;
+    regexes.insert(pattern.to_string(), regex.clone());
+    Ok(regex)
+}
+
+/// Find the given binary in the directories of the `PATH` environment variable.
+///
+/// # Arguments
+/// * `bin_name` - The name of the binary to find.
+///
+/// # Returns
+/// The path of the first match in `PATH` order, or None if not found.
+pub fn which(bin_name: &str) -> Option<PathBuf> {
+    let path_var = env::var_os("PATH")?;
+    env::split_paths(&path_var)
+        .map(|dir| dir.join(bin_name))
+        .find(|path| path.is_file())
+}
+
+/// Get the path of the given binary in `bin_dir`, if it is installed there.
+pub fn managed_path(bin_name: &str) -> Option<PathBuf> {
+    let path = crate::get_bin_dir().ok()?.join(bin_name);
+    path.exists().then_some(path)
+}
+
+/// Get the path of the given binary to probe for its version.
+///
+/// The copy installed in `bin_dir` is preferred over the first one in `PATH`,
+/// which may be a system copy.
+pub fn installed_path(bin_name: &str) -> Option<PathBuf> {
+    managed_path(bin_name).or_else(|| which(bin_name))
+}
+
+/// Check whether two paths point to the same file, following symlinks.
+pub fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
 /// Check if the given binary is managed by pixi.
 ///
 /// # Arguments
@@ -126,7 +391,7 @@ impl App {
 /// # Returns
 /// A boolean indicating whether the binary is managed by pixi.
 pub fn check_pixi_managed(bin_name: &str) -> bool {
-    if !Command::new("pixi").arg("--version").output().is_ok() {
+    if Command::new("pixi").arg("--version").output().is_err() {
         return false;
     }
 
@@ -144,41 +409,85 @@ pub fn check_pixi_managed(bin_name: &str) -> bool {
 
 impl AppStatus {
     pub fn new(app: &App, debug: bool) -> Self {
+        let managed_path = managed_path(&app.bin);
+        let resolved_path = which(&app.bin);
+        // Probe the binary in bin_dir rather than whatever comes first in PATH
+        let current_version = managed_path
+            .as_ref()
+            .or(resolved_path.as_ref())
+            .and_then(|path| get_current_version_with_debug(&path.to_string_lossy(), app, debug));
         Self {
             pixi_managed: Some(check_pixi_managed(&app.bin)),
-            current_version: get_current_version_with_debug(&app.bin, debug),
+            current_version,
             latest_version: None,
             app: app.clone(),
+            managed_path,
+            resolved_path,
+            held_back_version: None,
+        }
+    }
+
+    /// Describe a mismatch between the installed binary and the one run from `PATH`.
+    ///
+    /// # Returns
+    /// A warning if the binary in `bin_dir` is shadowed by another one earlier
+    /// in `PATH`, or is not in `PATH` at all.
+    pub fn path_warning(&self) -> Option<String> {
+        let managed = self.managed_path.as_ref()?;
+        match &self.resolved_path {
+            Some(resolved) if !same_file(managed, resolved) => Some(format!(
+                "{} runs {} from PATH, not the installed {}",
+                self.app.bin,
+                resolved.display(),
+                managed.display()
+            )),
+            Some(_) => None,
+            None => Some(format!("{} is not in PATH", managed.display())),
         }
     }
     pub fn is_pixi_managed(&self) -> bool {
-        self.pixi_managed.clone().unwrap_or(false)
+        self.pixi_managed.unwrap_or(false)
     }
     pub fn set_latest_version(&mut self, version: String) {
         self.latest_version = Some(version);
     }
 
+    /// Compare the current version with the latest version, using the
+    /// version scheme of the app.
+    ///
+    /// Returns the ordering of the current version relative to the latest one,
+    /// or None if either is unknown or they cannot be ordered.
+    pub fn compare_versions(&self) -> Option<Ordering> {
+        let (current, latest) = (
+            self.current_version.as_ref()?,
+            self.latest_version.as_ref()?,
+        );
+        self.app.version_scheme.compare(current, latest)
+    }
+
+    /// Check if the installed version is newer than the latest release,
+    /// as with a locally built version.
+    pub fn is_newer_than_latest(&self) -> bool {
+        self.compare_versions() == Some(Ordering::Greater)
+    }
+
     /// Check if a version update is needed.
     ///
-    /// This function compares the current version with the latest version.
-    /// If the latest version is greater than the current version, an update is needed.
-    /// If the versions cannot be parsed as semantic versions, a string comparison is used.
+    /// This function compares the current version with the latest version
+    /// according to the version scheme of the app. If the latest version is
+    /// greater than the current version, an update is needed. If the versions
+    /// cannot be ordered, any different version is an update.
     ///
     /// Returns `true` if an update is needed, `false` otherwise.
     pub fn is_version_update_needed(&self) -> bool {
         match (&self.current_version, &self.latest_version) {
             (None, None) => false,   // No idea, so do nothing
             (None, Some(_)) => true, // Not installed, so update needed
-            (Some(current_ver), Some(latest_ver)) => {
-                // Try to parse both versions as semantic versions
-                match (Version::parse(current_ver), Version::parse(latest_ver)) {
-                    (Ok(current_semver), Ok(latest_semver)) => latest_semver > current_semver,
-                    _ => {
-                        // Fall back to string comparison if parsing fails
-                        current_ver != latest_ver
-                    }
-                }
-            }
+            (Some(current_ver), Some(latest_ver)) => match self.compare_versions() {
+                Some(ordering) => ordering == Ordering::Less,
+                // Fall back to string comparison if the versions cannot be ordered
+                None => current_ver != latest_ver,
+            },
             (Some(_), None) => {
                 // Not installed, no update information
                 false
@@ -187,67 +496,243 @@ impl AppStatus {
     }
 }
 
+/// Maximum time a version probe may run before it is killed.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Time left to read the remaining output once a probed process has exited.
+const PROBE_DRAIN_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Kill a probed process and every process it started.
+fn kill_process_group(child: &mut Child) {
+    #[cfg(unix)]
+    // SAFETY: kill only sends a signal. The probe runs in its own process
+    // group, whose id is the pid of the child.
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+    let _ = child.kill();
+}
+
+/// Read a pipe of a probed process in the background.
+///
+/// Returns the buffer the output is appended to, and a receiver notified once
+/// the pipe is closed.
+fn read_pipe(pipe: Option<Box<dyn Read + Send>>) -> (Arc<Mutex<Vec<u8>>>, Receiver<()>) {
+    let buffer = Arc::new(Mutex::new(Vec::new()));
+    let (done, closed) = mpsc::channel();
+    let output = Arc::clone(&buffer);
+    thread::spawn(move || {
+        if let Some(mut pipe) = pipe {
+            let mut chunk = [0; 4096];
+            while let Ok(n @ 1..) = pipe.read(&mut chunk) {
+                output.lock().unwrap().extend_from_slice(&chunk[..n]);
+            }
+        }
+        let _ = done.send(());
+    });
+    (buffer, closed)
+}
+
+/// Run a binary to probe its version, without letting it block or take over the terminal.
+///
+/// stdin is `/dev/null`, pagers are disabled, and the process runs in its own
+/// process group, which is killed as a whole after `PROBE_TIMEOUT`. Once the
+/// process has exited, its output is read for at most `PROBE_DRAIN_TIMEOUT`,
+/// so that a background process keeping the pipes open cannot block the probe.
+///
+/// # Arguments
+/// * `bin_name` - The name or path of the binary to run.
+/// * `args` - The arguments to pass.
+///
+/// # Returns
+/// The output of the process, or an error if it could not be run or timed out.
+fn probe(bin_name: &str, args: &[&str]) -> anyhow::Result<Output> {
+    let mut command = Command::new(bin_name);
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .env("PAGER", "cat")
+        .env("GIT_PAGER", "cat")
+        .env("MANPAGER", "cat")
+        .env("BAT_PAGER", "")
+        .env("TERM", "dumb");
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    let mut child = command.spawn()?;
+    let stdout = read_pipe(child.stdout.take().map(|p| Box::new(p) as _));
+    let stderr = read_pipe(child.stderr.take().map(|p| Box::new(p) as _));
+
+    let deadline = Instant::now() + PROBE_TIMEOUT;
+    let status = loop {
+        match child.try_wait()? {
+            Some(status) => break Some(status),
+            None if Instant::now() < deadline => thread::sleep(Duration::from_millis(20)),
+            None => break None,
+        }
+    };
+    let Some(status) = status else {
+        // Kill the group before reaping the child, whose pid could otherwise be reused
+        kill_process_group(&mut child);
+        let _ = child.wait();
+        return Err(anyhow::anyhow!(
+            "timed out after {}s",
+            PROBE_TIMEOUT.as_secs()
+        ));
+    };
+
+    let drain_deadline = Instant::now() + PROBE_DRAIN_TIMEOUT;
+    let collect = |(buffer, closed): (Arc<Mutex<Vec<u8>>>, Receiver<()>)| {
+        let _ = closed.recv_timeout(drain_deadline.saturating_duration_since(Instant::now()));
+        std::mem::take(&mut *buffer.lock().unwrap())
+    };
+    Ok(Output {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    })
+}
+
+/// Extract a version from the output of a probe with the regex of an app.
+///
+/// Returns the first capture group of the regex, or the whole match if it has
+/// none, normalised for the version scheme of the app.
+fn extract_version_with_regex(s: &str, regex: &Regex, scheme: VersionScheme) -> Option<String> {
+    let captures = regex.captures(s)?;
+    captures
+        .get(1)
+        .or_else(|| captures.get(0))
+        .map(|m| scheme.normalize(m.as_str()))
+}
+
 /// Get the current version of the given binary.
 ///
+/// Every probe runs with `probe`, so that a binary waiting on stdin or
+/// starting a TUI cannot block. When the app sets `version_args`, only that
+/// command is probed; otherwise the usual version flags are tried. The
+/// version is extracted with `version_regex` if set.
+///
 /// # Arguments
 /// * `bin_name` - The name of the binary to check.
+/// * `app` - The app of the binary, for its version probing settings.
 /// * `debug` - Whether to print debug information.
 ///
 /// # Returns
 /// The current version of the binary, or None if it could not be determined.
-pub fn get_current_version_with_debug(bin_name: &str, debug: bool) -> Option<String> {
+pub fn get_current_version_with_debug(bin_name: &str, app: &App, debug: bool) -> Option<String> {
+    let regex = match app.version_regex.as_deref().map(compiled_regex) {
+        Some(Ok(regex)) => Some(regex),
+        Some(Err(e)) => {
+            say!("⚠️  Invalid version_regex for {}: {}", app.name, e);
+            None
+        }
+        None => None,
+    };
+    let extract = |s: &str| match &regex {
+        Some(regex) => extract_version_with_regex(s, regex, app.version_scheme),
+        None => app.version_scheme.extract(s),
+    };
+
+    // Use the configured command only
+    if let Some(args) = &app.version_args {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let version = match probe(bin_name, &args) {
+            Ok(output) => extract(&String::from_utf8_lossy(&output.stdout))
+                .or_else(|| extract(&String::from_utf8_lossy(&output.stderr))),
+            Err(e) => {
+                if debug {
+                    say!("🩺 [DEBUG] '{} {}' failed: {}", bin_name, args.join(" "), e);
+                }
+                None
+            }
+        };
+        if debug {
+            match &version {
+                Some(version) => say!(
+                    "🩺 [DEBUG] Version detected using '{} {}': {}",
+                    bin_name,
+                    args.join(" "),
+                    version
+                ),
+                None => say!(
+                    "🩺⚠️ [DEBUG] Could not detect version for '{}' using '{}'",
+                    bin_name,
+                    args.join(" ")
+                ),
+            }
+        }
+        return version;
+    }
+
     // Try different version flags in order of preference
     let version_flags = ["--version", "-V", "-v", "version"];
 
     for flag in &version_flags {
-        if let Ok(output) = Command::new(bin_name).arg(flag).output() {
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                let stderr = String::from_utf8_lossy(&output.stderr);
-
-                // Try to extract version from stdout first, then stderr
-                if let Some(version) = extract_version_from_string(&stdout) {
-                    if debug {
-                        println!(
-                            "🩺 [DEBUG] Version detected using '{} {}': {}",
-                            bin_name, flag, version
-                        );
-                    }
-                    return Some(version);
+        let output = probe(bin_name, &[flag]);
+        if debug && let Err(e) = &output {
+            say!("🩺 [DEBUG] '{} {}' failed: {}", bin_name, flag, e);
+        }
+        if let Ok(output) = output
+            && output.status.success()
+        {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+
+            // Try to extract version from stdout first, then stderr
+            if let Some(version) = extract(&stdout) {
+                if debug {
+                    say!(
+                        "🩺 [DEBUG] Version detected using '{} {}': {}",
+                        bin_name,
+                        flag,
+                        version
+                    );
                 }
-                if let Some(version) = extract_version_from_string(&stderr) {
-                    if debug {
-                        println!(
-                            "🩺 [DEBUG] Version detected using '{} {}' (from stderr): {}",
-                            bin_name, flag, version
-                        );
-                    }
-                    return Some(version);
+                return Some(version);
+            }
+            if let Some(version) = extract(&stderr) {
+                if debug {
+                    say!(
+                        "🩺 [DEBUG] Version detected using '{} {}' (from stderr): {}",
+                        bin_name,
+                        flag,
+                        version
+                    );
                 }
+                return Some(version);
             }
         }
     }
 
     // If no version flag worked, try running the command without arguments
     // Some apps print version info in help output
-    if let Ok(output) = Command::new(bin_name).output() {
+    if app.probe_without_args
+        && let Ok(output) = probe(bin_name, &[])
+    {
         let stdout = String::from_utf8_lossy(&output.stdout);
         let stderr = String::from_utf8_lossy(&output.stderr);
 
-        if let Some(version) = extract_version_from_string(&stdout) {
+        if let Some(version) = extract(&stdout) {
             if debug {
-                println!(
-                    "🩺 [DEBUG] Version detected from '{} {}' help output: {}",
-                    bin_name, "(no args)", version
+                say!(
+                    "🩺 [DEBUG] Version detected from '{} (no args)' help output: {}",
+                    bin_name,
+                    version
                 );
             }
             return Some(version);
         }
-        if let Some(version) = extract_version_from_string(&stderr) {
+        if let Some(version) = extract(&stderr) {
             if debug {
-                println!(
-                    "🩺 [DEBUG] Version detected from '{} {}' help output (stderr): {}",
-                    bin_name, "(no args)", version
+                say!(
+                    "🩺 [DEBUG] Version detected from '{} (no args)' help output (stderr): {}",
+                    bin_name,
+                    version
                 );
             }
             return Some(version);
@@ -255,7 +740,7 @@ pub fn get_current_version_with_debug(bin_name: &str, debug: bool) -> Option<Str
     }
 
     if debug {
-        println!(
+        say!(
             "🩺⚠️ [DEBUG] Could not detect version for '{}' using any method",
             bin_name
         );
@@ -263,24 +748,194 @@ pub fn get_current_version_with_debug(bin_name: &str, debug: bool) -> Option<Str
     None
 }
 
-/// Parse version from string - handles various version formats
-pub fn extract_version_from_string(s: &str) -> Option<String> {
-    // Try different version patterns in order of preference
-    let patterns = [
+/// A version with up to three numbers, prerelease and build metadata.
+static SEMVER_LIKE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[vV]?(\d+)(?:\.(\d+))?(?:\.(\d+))?(-[0-9A-Za-z.-]+)?(\+[0-9A-Za-z.-]+)?$")
+        .unwrap()
+});
+
+/// Versions searched in strings, in order of preference.
+static VERSION_PATTERNS: LazyLock<[Regex; 2]> = LazyLock::new(|| {
+    // Optional prerelease and build metadata, made of dot-separated identifiers
+    let suffix = r"(-[0-9A-Za-z-]+(?:\.[0-9A-Za-z-]+)*)?(\+[0-9A-Za-z-]+(?:\.[0-9A-Za-z-]+)*)?";
+    [
         r"(\d{1,5}\.\d{1,5}\.\d{1,5}(?:\.\d{1,5})?)", // x.y.z or x.y.z.w
-        r"v(\d{1,5}\.\d{1,5}\.\d{1,5}(?:\.\d{1,5})?)", // v-prefixed versions
-        r"version\s+(\d{1,5}\.\d{1,5}\.\d{1,5}(?:\.\d{1,5})?)", // "version x.y.z"
         r"(\d{1,5}\.\d{1,5})",                        // x.y (two-part versions)
-    ];
+    ]
+    .map(|pattern| Regex::new(&format!("{}{}", pattern, suffix)).unwrap())
+});
 
-    for pattern in &patterns {
-        if let Ok(re) = Regex::new(pattern) {
-            if let Some(cap) = re.captures(s) {
-                if let Some(version) = cap.get(1) {
-                    return Some(version.as_str().to_string());
-                }
+/// Normalise a version to a semantic version when possible.
+///
+/// A leading `v` is removed and missing minor and patch numbers are padded
+/// with zeros, keeping prerelease and build metadata: `v1.7` becomes `1.7.0`
+/// and `1.0-beta.2` becomes `1.0.0-beta.2`. Anything else, such as `x.y.z.w`
+/// versions, is returned unchanged.
+pub fn normalize_version(version: &str) -> String {
+    let Some(caps) = SEMVER_LIKE.captures(version.trim()) else {
+        return version.to_string();
+    };
+    let number = |i: usize| {
+        caps.get(i)
+            .map_or(Some(0), |m| m.as_str().parse::<u64>().ok())
+    };
+    let (Some(major), Some(minor), Some(patch)) = (number(1), number(2), number(3)) else {
+        return version.to_string();
+    };
+    let normalized = format!(
+        "{}.{}.{}{}{}",
+        major,
+        minor,
+        patch,
+        caps.get(4).map_or("", |m| m.as_str()),
+        caps.get(5).map_or("", |m| m.as_str())
+    );
+    if Version::parse(&normalized).is_ok() {
+        normalized
+    } else {
+        version.to_string()
+    }
+}
+
+/// Check whether the suffix of a version (without its `-`) is a prerelease.
+///
+/// Prereleases start with `alpha`, `beta`, `rc`, `pre` or `dev` (`rc1`,
+/// `beta.2`, `preview`) or are made of numeric identifiers (`1`, `2.1`), while
+/// targets such as `linux` or `musl` are not.
+fn is_prerelease(suffix: &str) -> bool {
+    let first = suffix.split('.').next().unwrap_or_default().to_lowercase();
+    ["alpha", "beta", "rc", "pre", "dev"]
+        .iter()
+        .any(|tag| first.starts_with(tag))
+        || suffix
+            .split('.')
+            .all(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Parse version from string - handles various version formats
+///
+/// The first `x.y.z` (or `x.y.z.w`) version found is preferred, then `x.y`.
+/// Prerelease and build metadata are kept (`1.0.0-beta.2+build.5`), and the
+/// version is normalised with `normalize_version`. A suffix that is not a
+/// prerelease, such as the target of `1.2.3-linux`, is left out.
+pub fn extract_version_from_string(s: &str) -> Option<String> {
+    // Try different version patterns in order of preference
+    for re in VERSION_PATTERNS.iter() {
+        if let Some(cap) = re.captures(s)
+            && let Some(core) = cap.get(1)
+        {
+            // x.y.z.w versions are not semantic versions, keep them as they are
+            if core.as_str().matches('.').count() == 3 {
+                return Some(core.as_str().to_string());
             }
+            // A suffix running into an identifier (`1.2.3-x86_64`) is not a prerelease
+            let whole = cap.get(0).unwrap();
+            let prerelease = cap.get(2).map(|m| &m.as_str()[1..]);
+            let version = if s[whole.end()..].starts_with('_')
+                || prerelease.is_some_and(|prerelease| !is_prerelease(prerelease))
+            {
+                core.as_str()
+            } else {
+                whole.as_str()
+            };
+            return Some(normalize_version(version));
         }
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_keeps_prereleases() {
+        let extract = |s| extract_version_from_string(s);
+        assert_eq!(extract("v1.2.3-rc1").as_deref(), Some("1.2.3-rc1"));
+        assert_eq!(
+            extract("tool 1.0.0-beta.2+build.5").as_deref(),
+            Some("1.0.0-beta.2+build.5")
+        );
+        assert_eq!(extract("1.2.3-alpha").as_deref(), Some("1.2.3-alpha"));
+        assert_eq!(extract("1.2.3-dev.4").as_deref(), Some("1.2.3-dev.4"));
+        assert_eq!(extract("1.2.3-1.2").as_deref(), Some("1.2.3-1.2"));
+        assert_eq!(extract("v1.7-pre").as_deref(), Some("1.7.0-pre"));
+    }
+
+    #[test]
+    fn extract_drops_targets() {
+        let extract = |s| extract_version_from_string(s);
+        assert_eq!(extract("tool-1.2.3-linux").as_deref(), Some("1.2.3"));
+        assert_eq!(extract("tool-1.2.3-musl").as_deref(), Some("1.2.3"));
+        assert_eq!(
+            extract("tool-1.2.3-x86_64-unknown-linux-gnu.tar.gz").as_deref(),
+            Some("1.2.3")
+        );
+        assert_eq!(extract("1.2.3-linux+build").as_deref(), Some("1.2.3"));
+        assert_eq!(extract("ripgrep 14.1.0").as_deref(), Some("14.1.0"));
+        assert_eq!(extract("10.0.19041.1").as_deref(), Some("10.0.19041.1"));
+    }
+
+    #[test]
+    fn prefixed_tags() {
+        let cli = App {
+            tag_prefix: Some("cli-v".to_string()),
+            ..App::default()
+        };
+        assert_eq!(cli.version_from_tag("cli-v2.3.1").as_deref(), Some("2.3.1"));
+        assert_eq!(cli.version_from_tag("lib-v0.4.0"), None);
+        assert_eq!(cli.version_from_tag("v2.3.1"), None);
+
+        let jq = App {
+            tag_prefix: Some("jq-".to_string()),
+            ..App::default()
+        };
+        assert_eq!(jq.version_from_tag("jq-1.7.1").as_deref(), Some("1.7.1"));
+        assert_eq!(jq.version_from_tag("1.7.1"), None);
+        assert!(!App::default().filters_tags() && jq.filters_tags());
+    }
+
+    #[test]
+    fn tags_matching_a_regex() {
+        let app = App {
+            tag_regex: Some(r"^release/[0-9-]+-([0-9.]+)$".to_string()),
+            ..App::default()
+        };
+        assert_eq!(
+            app.version_from_tag("release/2024-05-01-1.2.3").as_deref(),
+            Some("1.2.3")
+        );
+        assert_eq!(app.version_from_tag("v1.2.3"), None);
+        assert!(app.validate().is_ok());
+
+        // Without a capture group, the whole match is the version
+        let app = App {
+            tag_prefix: Some("tool/".to_string()),
+            tag_regex: Some(r"\d+\.\d+".to_string()),
+            ..App::default()
+        };
+        assert_eq!(app.version_from_tag("tool/v1.7").as_deref(), Some("1.7.0"));
+        assert_eq!(app.version_from_tag("other/v1.7"), None);
+
+        let invalid = App {
+            tag_regex: Some("release-(".to_string()),
+            ..App::default()
+        };
+        assert!(invalid.validate().is_err());
+        assert_eq!(invalid.version_from_tag("release-1.2.3"), None);
+    }
+
+    #[test]
+    fn string_tags_are_kept_whole() {
+        let app = App {
+            tag_prefix: Some("cli-".to_string()),
+            version_scheme: VersionScheme::String,
+            ..App::default()
+        };
+        assert_eq!(
+            app.version_from_tag("cli-nightly-abc123").as_deref(),
+            Some("nightly-abc123")
+        );
+        assert_eq!(app.version_from_tag("nightly-abc123"), None);
+    }
+}
@@ -0,0 +1,278 @@
+use crate::app::App;
+use crate::github::{self, Release};
+use crate::http::HttpClient;
+use crate::say;
+use anyhow::Result;
+use regex::Regex;
+use std::cmp::Ordering;
+use std::sync::LazyLock;
+
+/// A markdown heading, capturing its level and its title.
+static HEADING: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(#{1,6})\s+(.*?)\s*#*$").unwrap());
+
+/// A list item, capturing its indentation and its text.
+static BULLET: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\s*)[*+-]\s+(.*)$").unwrap());
+
+/// An inline image.
+static IMAGE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"!\[[^\]]*\]\([^)]*\)").unwrap());
+
+/// A link, capturing its text and its URL.
+static LINK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[([^\]]+)\]\(([^)\s]+)\)").unwrap());
+
+/// Emphasis and inline code markers.
+static EMPHASIS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\*\*|__|`").unwrap());
+
+/// Select the releases of an app after `current` up to `latest`, newest first.
+///
+/// Only the releases whose tag belongs to the app are considered (see
+/// `App::version_from_tag`). Versions are compared with the version scheme
+/// of the app. When they cannot be ordered, the releases listed before the
+/// one of `current` are selected, or only the latest one if `current` is not
+/// found.
+///
+/// # Arguments
+/// * `releases` - The releases of the repository, newest first.
+/// * `app` - The app, for its tag settings.
+/// * `current` - The installed version.
+/// * `latest` - The latest version.
+///
+/// # Returns
+/// The releases between the two versions.
+pub fn releases_between<'a>(
+    releases: &'a [Release],
+    app: &App,
+    current: &str,
+    latest: &str,
+) -> Vec<&'a Release> {
+    let releases = releases
+        .iter()
+        .filter(|release| !release.draft)
+        .filter_map(|release| Some((release, app.version_from_tag(&release.tag_name)?)));
+    let scheme = app.version_scheme;
+    match scheme.compare(current, latest) {
+        Some(_) => releases
+            .filter(|(_, version)| {
+                scheme.compare(current, version) == Some(Ordering::Less)
+                    && scheme
+                        .compare(version, latest)
+                        .is_some_and(|ordering| ordering != Ordering::Greater)
+            })
+            .map(|(release, _)| release)
+            .collect(),
+        None => {
+            let releases: Vec<(&Release, String)> = releases
+                .skip_while(|(_, version)| version != latest)
+                .collect();
+            let found = releases.iter().any(|(_, version)| version == current);
+            releases
+                .into_iter()
+                .take_while(|(_, version)| version != current)
+                .take(if found { usize::MAX } else { 1 })
+                .map(|(release, _)| release)
+                .collect()
+        }
+    }
+}
+
+/// Render the markdown of release notes as plain text for the terminal.
+///
+/// Headings are underlined, list markers become bullets, emphasis markers
+/// and images are dropped, links show their URL and code blocks are indented.
+///
+/// # Arguments
+/// * `markdown` - The markdown to render.
+///
+/// # Returns
+/// The rendered text.
+pub fn render_markdown(markdown: &str) -> String {
+    let inline = |text: &str| -> String {
+        let text = IMAGE.replace_all(text, "");
+        let text = LINK.replace_all(&text, |caps: &regex::Captures| {
+            if caps[1] == caps[2] {
+                caps[1].to_string()
+            } else {
+                format!("{} ({})", &caps[1], &caps[2])
+            }
+        });
+        EMPHASIS.replace_all(&text, "").trim_end().to_string()
+    };
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut in_code = false;
+    for line in markdown.lines() {
+        let line = line.trim_end();
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            lines.push(format!("    {}", line));
+        } else if line.trim_start().starts_with("<!--") {
+            continue;
+        } else if let Some(caps) = HEADING.captures(line) {
+            let title = inline(&caps[2]);
+            if caps[1].len() <= 2 {
+                let underline = if caps[1].len() == 1 { "═" } else { "─" };
+                lines.push(title.clone());
+                lines.push(underline.repeat(title.chars().count()));
+            } else {
+                lines.push(format!("▸ {}", title));
+            }
+        } else if let Some(caps) = BULLET.captures(line) {
+            lines.push(format!("{}• {}", &caps[1], inline(&caps[2])));
+        } else {
+            lines.push(inline(line));
+        }
+    }
+
+    // Collapse runs of blank lines
+    lines.dedup_by(|a, b| a.is_empty() && b.is_empty());
+    lines.join("\n").trim().to_string()
+}
+
+/// Fetch and print the release notes between the installed and the latest version.
+///
+/// # Arguments
+/// * `http` - The HTTP client to use.
+/// * `app` - The app, with its repository and tag settings.
+/// * `current` - The installed version.
+/// * `latest` - The latest version.
+///
+/// # Returns
+/// A `Result` which is `Ok` if the release notes were fetched, or an error.
+pub async fn print_changelog(
+    http: &HttpClient,
+    app: &App,
+    current: &str,
+    latest: &str,
+) -> Result<()> {
+    let token = std::env::var("GITHUB_TOKEN").ok();
+    let releases = github::fetch_releases(http, app.get_repo(), token.as_deref()).await?;
+    let releases = releases_between(&releases, app, current, latest);
+    if releases.is_empty() {
+        say!(
+            "   📝 No release notes found between {} and {}",
+            current,
+            latest
+        );
+        return Ok(());
+    }
+
+    say!("   📝 Changes from {} to {}:", current, latest);
+    for release in releases {
+        let title = release
+            .name
+            .as_deref()
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or(&release.tag_name);
+        match release.published_at {
+            Some(published_at) => say!("\n   ── {} ({})", title, published_at.format("%Y-%m-%d")),
+            None => say!("\n   ── {}", title),
+        }
+        let body = release.body.as_deref().unwrap_or_default();
+        if body.trim().is_empty() {
+            say!("      (no release notes)");
+        }
+        for line in render_markdown(body).lines() {
+            if line.is_empty() {
+                say!();
+            } else {
+                say!("      {}", line);
+            }
+        }
+    }
+    say!();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::VersionScheme;
+
+    fn releases(tags: &[&str]) -> Vec<Release> {
+        tags.iter()
+            .map(|tag| Release {
+                tag_name: tag.to_string(),
+                ..Release::default()
+            })
+            .collect()
+    }
+
+    fn tags(releases: Vec<&Release>) -> Vec<&str> {
+        releases
+            .iter()
+            .map(|release| release.tag_name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn releases_between_versions() {
+        let app = App::default();
+        let all = releases(&["v2.0.0", "v1.3.0", "v1.2.4", "v1.2.3", "v1.2.2"]);
+        // Exclusive of the installed version, inclusive of the latest one
+        assert_eq!(
+            tags(releases_between(&all, &app, "1.2.3", "1.3.0")),
+            ["v1.3.0", "v1.2.4"]
+        );
+        // The installed version does not need a release
+        assert_eq!(
+            tags(releases_between(&all, &app, "1.2.3-local", "1.2.4")),
+            ["v1.2.4", "v1.2.3"]
+        );
+        assert!(releases_between(&all, &app, "2.0.0", "2.0.0").is_empty());
+    }
+
+    #[test]
+    fn releases_between_unordered_versions() {
+        let app = App {
+            version_scheme: VersionScheme::String,
+            ..App::default()
+        };
+        let all = releases(&["nightly-c", "nightly-b", "nightly-a"]);
+        assert_eq!(
+            tags(releases_between(&all, &app, "nightly-a", "nightly-c")),
+            ["nightly-c", "nightly-b"]
+        );
+        // Unknown installed version: only the latest release
+        assert_eq!(
+            tags(releases_between(&all, &app, "nightly-0", "nightly-b")),
+            ["nightly-b"]
+        );
+        assert!(releases_between(&all, &app, "nightly-a", "nightly-d").is_empty());
+    }
+
+    #[test]
+    fn render_markdown_for_the_terminal() {
+        let markdown = "\
+# Release 1.3.0 #
+Some **bold** and `code` text, see [the docs](https://example.com/docs).
+
+<!-- generated -->
+## Features
+- Add [https://example.com](https://example.com)
+  * nested ![logo](logo.png)
+
+
+### Fixes
+```
+cargo install
+```";
+        assert_eq!(
+            render_markdown(markdown),
+            "\
+Release 1.3.0
+═════════════
+Some bold and code text, see the docs (https://example.com/docs).
+
+Features
+────────
+• Add https://example.com
+  • nested
+
+▸ Fixes
+    cargo install"
+        );
+    }
+}
@@ -0,0 +1,491 @@
+use crate::Config;
+use crate::app::{self, App};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+/// Check whether a line starts a top-level key of the configuration file.
+fn is_top_level_key(line: &str) -> bool {
+    !line.is_empty()
+        && !line.starts_with(char::is_whitespace)
+        && !line.starts_with('#')
+        && !line.starts_with('-')
+}
+
+/// Get a line without its trailing comment and whitespace.
+///
+/// A `#` starts a comment at the beginning of the line or after whitespace,
+/// outside of quoted strings. Quotes only open a string at the start of a value.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '#') if previous.is_whitespace() => return line[..i].trim_end(),
+            (None, '"' | '\'') if previous.is_whitespace() || "[{,".contains(previous) => {
+                quote = Some(c)
+            }
+            (Some(q), _) if c == q => quote = None,
+            _ => {}
+        }
+        previous = c;
+    }
+    line.trim_end()
+}
+
+/// Serialize an app as a YAML list item, leaving out empty fields.
+///
+/// # Arguments
+/// * `app` - The app to serialize.
+/// * `indent` - The indentation of the list items in the configuration file.
+///
+/// # Returns
+/// The lines of the list item, terminated by a newline.
+pub fn app_entry(app: &App, indent: &str) -> Result<String> {
+    let mut value = serde_yaml::to_value(app)?;
+    if let Some(mapping) = value.as_mapping_mut() {
+        mapping.retain(|_, v| !v.is_null());
+    }
+    let yaml = serde_yaml::to_string(&vec![value])?;
+    Ok(yaml
+        .lines()
+        .map(|line| format!("{}{}\n", indent, line))
+        .collect())
+}
+
+/// Location of the `apps` list in the lines of a configuration file.
+struct AppsList {
+    /// Index of the `apps:` line
+    start: usize,
+    /// Index of the first line after the list
+    end: usize,
+    /// Indentation of the list items
+    indent: String,
+}
+
+impl AppsList {
+    /// Find the `apps` list in the lines of a configuration file.
+    fn find(lines: &[&str]) -> Result<Self> {
+        let start = lines
+            .iter()
+            .position(|line| matches!(strip_comment(line), "apps:" | "apps: []"))
+            .ok_or_else(|| anyhow::anyhow!("No `apps:` list found in the configuration file"))?;
+
+        // The list ends at the next top-level key
+        let end = lines[start + 1..]
+            .iter()
+            .position(|line| is_top_level_key(line))
+            .map_or(lines.len(), |i| start + 1 + i);
+
+        let indent = lines[start + 1..end]
+            .iter()
+            .find(|line| line.trim_start().starts_with("- "))
+            .map_or("  ", |line| &line[..line.len() - line.trim_start().len()])
+            .to_string();
+
+        Ok(Self { start, end, indent })
+    }
+
+    /// Get the entries of the list as `(first line, line after the last content line)`.
+    ///
+    /// Comments and blank lines after an entry belong to the next one.
+    fn entries(&self, lines: &[&str]) -> Vec<(usize, usize)> {
+        let item = format!("{}- ", self.indent);
+        let starts: Vec<usize> = (self.start + 1..self.end)
+            .filter(|&i| lines[i].starts_with(&item))
+            .collect();
+        starts
+            .iter()
+            .enumerate()
+            .map(|(n, &first)| {
+                let next = starts.get(n + 1).copied().unwrap_or(self.end);
+                let last = (first..next)
+                    .rev()
+                    .find(|&i| {
+                        let trimmed = lines[i].trim_start();
+                        !trimmed.is_empty() && !trimmed.starts_with('#')
+                    })
+                    .unwrap_or(first);
+                (first, last + 1)
+            })
+            .collect()
+    }
+
+    /// Find the entry of the given app.
+    fn find_entry(&self, lines: &[&str], name: &str) -> Result<(usize, usize)> {
+        self.entries(lines)
+            .into_iter()
+            .find(|&(first, end)| {
+                let entry: String = lines[first..end]
+                    .iter()
+                    .map(|line| format!("{}\n", line.get(self.indent.len()..).unwrap_or("")))
+                    .collect();
+                serde_yaml::from_str::<Vec<App>>(&entry)
+                    .is_ok_and(|apps| apps.first().is_some_and(|app| app.name == name))
+            })
+            .ok_or_else(|| anyhow::anyhow!("App '{}' not found in the configuration file", name))
+    }
+}
+
+/// Join lines into the content of a file.
+fn join_lines(lines: &[String]) -> String {
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+/// Parse an edited configuration to make sure it is still valid.
+fn validate(content: &str) -> Result<Config> {
+    let config: Config =
+        serde_yaml::from_str(content).context("The edited configuration is not valid")?;
+    for app in &config.apps {
+        app.validate()
+            .context("The edited configuration is not valid")?;
+    }
+    Ok(config)
+}
+
+/// Append an app to the `apps` list of a configuration file.
+///
+/// The file is edited as text so that comments, ordering and formatting of
+/// the existing entries are preserved. The new entry is inserted after the
+/// last entry of the list, with the same indentation.
+///
+/// # Arguments
+/// * `content` - The content of the configuration file.
+/// * `app` - The app to append.
+///
+/// # Returns
+/// The new content of the configuration file, or an error if the `apps` list
+/// could not be found or the result is not a valid configuration.
+pub fn append_app(content: &str, app: &App) -> Result<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let list = AppsList::find(&lines)?;
+
+    // Insert after the last line of the last entry, before trailing comments
+    let insert_at = list
+        .entries(&lines)
+        .last()
+        .map_or(list.start + 1, |&(_, end)| end);
+
+    let mut result: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+    if strip_comment(lines[list.start]) == "apps: []" {
+        result[list.start] = lines[list.start].replacen("apps: []", "apps:", 1);
+    }
+    let entry = app_entry(app, &list.indent)?;
+    result.splice(insert_at..insert_at, entry.lines().map(String::from));
+
+    let result = join_lines(&result);
+    if !validate(&result)?.apps.iter().any(|a| a.name == app.name) {
+        return Err(anyhow::anyhow!(
+            "Failed to append the app to the configuration"
+        ));
+    }
+    Ok(result)
+}
+
+/// Remove an app from the `apps` list of a configuration file.
+///
+/// Only the lines of the entry are removed, the rest of the file is left untouched.
+///
+/// # Arguments
+/// * `content` - The content of the configuration file.
+/// * `name` - The name of the app to remove.
+///
+/// # Returns
+/// The new content of the configuration file, or an error if the app could
+/// not be found or the result is not a valid configuration.
+pub fn remove_app(content: &str, name: &str) -> Result<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let list = AppsList::find(&lines)?;
+    let (first, end) = list.find_entry(&lines, name)?;
+
+    let mut result: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+    result.drain(first..end);
+    if list.entries(&lines).len() == 1 {
+        // An empty list must stay a list
+        result[list.start] = lines[list.start].replacen("apps:", "apps: []", 1);
+    }
+
+    let result = join_lines(&result);
+    if validate(&result)?.apps.iter().any(|a| a.name == name) {
+        return Err(anyhow::anyhow!(
+            "Failed to remove the app from the configuration"
+        ));
+    }
+    Ok(result)
+}
+
+/// Set a field of an app in a configuration file.
+///
+/// The value is parsed as YAML, so `true`, `3` or `[a, b]` are typed values
+/// and anything else is a string. Setting a field to `null` removes it.
+/// An existing field is replaced in place, keeping its trailing comment, and
+/// a new field is added at the end of the entry.
+///
+/// # Arguments
+/// * `content` - The content of the configuration file.
+/// * `name` - The name of the app to edit.
+/// * `key` - The field to set.
+/// * `value` - The new value of the field.
+///
+/// # Returns
+/// The new content of the configuration file, or an error if the app or the
+/// field is unknown, or the result is not a valid configuration.
+pub fn set_field(content: &str, name: &str, key: &str, value: &str) -> Result<String> {
+    if !app::FIELDS.contains(&key) {
+        return Err(anyhow::anyhow!(
+            "Unknown field '{}', expected one of: {}",
+            key,
+            app::FIELDS.join(", ")
+        ));
+    }
+    let value: serde_yaml::Value =
+        serde_yaml::from_str(value).unwrap_or_else(|_| serde_yaml::Value::from(value));
+
+    let lines: Vec<&str> = content.lines().collect();
+    let list = AppsList::find(&lines)?;
+    let (first, end) = list.find_entry(&lines, name)?;
+
+    // Fields are aligned with the first key of the entry, after "- "
+    let column = list.indent.len() + 2;
+    let field_indent = " ".repeat(column);
+    let prefix = format!("{}:", key);
+    let field_line = (first..end).find(|&i| {
+        lines[i]
+            .get(column..)
+            .is_some_and(|field| field.starts_with(&prefix))
+            && (i == first || lines[i].starts_with(&field_indent))
+    });
+
+    // The lines of the new field
+    let mut field = Vec::new();
+    if !value.is_null() {
+        let yaml = serde_yaml::to_string(&value)?;
+        let yaml = yaml.trim_end();
+        // Block sequences and mappings go on the lines after the key, even with a single item
+        let block = (value.is_sequence() && !yaml.starts_with('['))
+            || (value.is_mapping() && !yaml.starts_with('{'));
+        if block || yaml.contains('\n') {
+            field.push(format!("{}:", key));
+            field.extend(yaml.lines().map(|line| format!("  {}", line)));
+        } else {
+            field.push(format!("{}: {}", key, yaml));
+        }
+    }
+
+    let mut result: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+    match field_line {
+        Some(i) => {
+            // The value spans the lines indented deeper than the key (or list items at its level)
+            let value_end = (i + 1..end)
+                .find(|&j| {
+                    let line = lines[j];
+                    let deeper = line.trim().is_empty()
+                        || line.len() > column
+                            && line.starts_with(&field_indent)
+                            && (line[column..].starts_with(char::is_whitespace)
+                                || line[column..].starts_with("- "));
+                    !deeper
+                })
+                .unwrap_or(end);
+            if i == first && field.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "'{}' is the first field of the entry and cannot be removed",
+                    key
+                ));
+            }
+            let mut replacement: Vec<String> = field
+                .iter()
+                .map(|line| format!("{}{}", field_indent, line))
+                .collect();
+            if i == first
+                && let Some(line) = replacement.first_mut()
+            {
+                *line = format!("{}- {}", list.indent, &line[column..]);
+            }
+            // Keep the comment at the end of the key line
+            let comment = &lines[i][strip_comment(lines[i]).len()..];
+            if let Some(line) = replacement.first_mut()
+                && !comment.trim().is_empty()
+            {
+                line.push_str(comment);
+            }
+            result.splice(i..value_end, replacement);
+        }
+        None if field.is_empty() => {
+            return Err(anyhow::anyhow!("'{}' is not set for {}", key, name));
+        }
+        None => {
+            result.splice(
+                end..end,
+                field.iter().map(|line| format!("{}{}", field_indent, line)),
+            );
+        }
+    }
+
+    let result = join_lines(&result);
+    let new_name = match (key, value.as_str()) {
+        ("name", Some(new_name)) => new_name,
+        _ => name,
+    };
+    if !validate(&result)?.apps.iter().any(|a| a.name == new_name) {
+        return Err(anyhow::anyhow!(
+            "Failed to set {} for {} in the configuration",
+            key,
+            name
+        ));
+    }
+    Ok(result)
+}
+
+/// Write an edited configuration file, keeping a backup of the previous one.
+///
+/// The previous content is copied to `{path}.bak` and the new content is
+/// written to a temporary file renamed over the configuration file, so that
+/// an interrupted write never leaves a truncated configuration.
+///
+/// # Arguments
+/// * `path` - The path of the configuration file.
+/// * `content` - The new content of the file.
+pub fn write_config(path: &Path, content: &str) -> Result<()> {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    fs::copy(path, &backup).with_context(|| {
+        format!(
+            "Failed to back up config file to {}",
+            Path::new(&backup).display()
+        )
+    })?;
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, content)
+        .with_context(|| format!("Failed to write config file: {}", path.display()))?;
+    fs::rename(&tmp, path)
+        .with_context(|| format!("Failed to write config file: {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = "\
+# Tools installed on this machine
+settings:
+  keep_versions: 2 # rollbacks
+apps: # managed apps
+  # Disk usage
+  - name: dust
+    bin: dust
+    repo: bootandy/dust # fast
+
+  # Pager
+  - name: bat
+    bin: bat
+    repo: sharkdp/bat
+";
+
+    fn app(name: &str) -> App {
+        App {
+            name: name.to_string(),
+            bin: name.to_string(),
+            repo: Some(format!("owner/{}", name)),
+            ..App::default()
+        }
+    }
+
+    #[test]
+    fn strip_comment_respects_quotes() {
+        assert_eq!(strip_comment("apps: # managed apps"), "apps:");
+        assert_eq!(strip_comment("# comment"), "");
+        assert_eq!(
+            strip_comment(r#"  version_regex: "v # (\d+)" # comment"#),
+            r#"  version_regex: "v # (\d+)""#
+        );
+        assert_eq!(
+            strip_comment("  description: 'a # b' # c"),
+            "  description: 'a # b'"
+        );
+        assert_eq!(strip_comment("  repo: a#b"), "  repo: a#b");
+        assert_eq!(
+            strip_comment("  description: it's # c"),
+            "  description: it's"
+        );
+    }
+
+    #[test]
+    fn append_keeps_comments() {
+        let result = append_app(CONFIG, &app("uv")).unwrap();
+        assert!(result.starts_with(CONFIG));
+        assert!(result.ends_with("  - name: uv\n    bin: uv\n    repo: owner/uv\n"));
+    }
+
+    #[test]
+    fn remove_keeps_comments() {
+        let result = remove_app(CONFIG, "dust").unwrap();
+        assert_eq!(
+            result,
+            CONFIG.replace(
+                "  - name: dust\n    bin: dust\n    repo: bootandy/dust # fast\n",
+                ""
+            )
+        );
+        assert!(result.contains("# Disk usage"));
+        let config = validate(&result).unwrap();
+        assert_eq!(config.apps.len(), 1);
+        assert_eq!(config.apps[0].name, "bat");
+    }
+
+    #[test]
+    fn set_keeps_comments() {
+        let result = set_field(CONFIG, "dust", "repo", "someone/dust").unwrap();
+        assert_eq!(
+            result,
+            CONFIG.replace("repo: bootandy/dust # fast", "repo: someone/dust # fast")
+        );
+        let result = set_field(CONFIG, "dust", "repo", "[a, b]").unwrap_err();
+        assert!(result.to_string().contains("not valid"));
+        let config = CONFIG.replace("- name: dust", "- name: dust # disk");
+        let result = set_field(&config, "dust", "name", "du").unwrap();
+        assert_eq!(
+            result,
+            config.replace("name: dust # disk", "name: du # disk")
+        );
+        let result = set_field(CONFIG, "dust", "version_args", "[--version, -v]").unwrap();
+        assert!(result.contains("    version_args:\n      - --version\n      - -v\n"));
+        let result = set_field(&result, "dust", "version_args", "[-V]").unwrap();
+        assert!(result.contains("    version_args:\n      - -V\n\n  # Pager"));
+        let result = set_field(CONFIG, "bat", "keep_versions", "3").unwrap();
+        assert_eq!(
+            result,
+            CONFIG.replace(
+                "repo: sharkdp/bat\n",
+                "repo: sharkdp/bat\n    keep_versions: 3\n"
+            )
+        );
+    }
+
+    #[test]
+    fn invalid_edits_are_rejected() {
+        assert!(set_field(CONFIG, "bat", "keep_versions", "many").is_err());
+        assert!(set_field(CONFIG, "bat", "unknown", "1").is_err());
+        assert!(set_field(CONFIG, "bat", "tag_regex", "v(").is_err());
+        assert!(set_field(CONFIG, "bat", "name", "null").is_err());
+        assert!(remove_app(CONFIG, "uv").is_err());
+    }
+
+    #[test]
+    fn write_config_keeps_a_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        fs::write(&path, CONFIG).unwrap();
+        let edited = remove_app(CONFIG, "bat").unwrap();
+        write_config(&path, &edited).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), edited);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("config.yaml.bak")).unwrap(),
+            CONFIG
+        );
+        assert!(!dir.path().join("config.yaml.tmp").exists());
+    }
+}
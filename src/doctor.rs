@@ -0,0 +1,208 @@
+use crate::app::{self, App, same_file};
+use crate::github;
+use crate::http::HttpClient;
+use crate::say;
+use anyhow::Result;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Findings of the diagnosis.
+#[derive(Default)]
+struct Report {
+    warnings: usize,
+    errors: usize,
+}
+
+impl Report {
+    fn ok(&self, message: String) {
+        say!("✅ {}", message);
+    }
+
+    fn info(&self, message: String) {
+        say!("ℹ️  {}", message);
+    }
+
+    fn warning(&mut self, message: String) {
+        say!("⚠️  {}", message);
+        self.warnings += 1;
+    }
+
+    fn error(&mut self, message: String) {
+        say!("❌ {}", message);
+        self.errors += 1;
+    }
+}
+
+/// Check where `bin_dir` sits in `PATH`.
+fn check_path(report: &mut Report, bin_dir: &Path) {
+    let path_var = env::var_os("PATH").unwrap_or_default();
+    let dirs: Vec<_> = env::split_paths(&path_var).collect();
+    match dirs.iter().position(|dir| same_file(dir, bin_dir)) {
+        Some(position) => report.ok(format!(
+            "{} is on PATH (position {} of {})",
+            bin_dir.display(),
+            position + 1,
+            dirs.len()
+        )),
+        None => report.error(format!(
+            "{} is not on PATH, add it with: export PATH=\"{}:$PATH\"",
+            bin_dir.display(),
+            bin_dir.display()
+        )),
+    }
+}
+
+/// Check that the installed binaries are the ones found first in `PATH`.
+fn check_shadowed(report: &mut Report, apps: &[App], bin_dir: &Path) {
+    let mut shadowed = 0;
+    for app in apps {
+        for bin in app.binaries() {
+            let managed = bin_dir.join(bin.target());
+            if !managed.exists() {
+                continue;
+            }
+            if let Some(found) = app::which(bin.target())
+                && !same_file(&found, &managed)
+            {
+                report.warning(format!(
+                    "{} resolves to {} earlier in PATH, not to {}",
+                    bin.target(),
+                    found.display(),
+                    managed.display()
+                ));
+                shadowed += 1;
+            }
+        }
+    }
+    if shadowed == 0 {
+        report.ok("No installed binary is shadowed by another one in PATH".to_string());
+    }
+}
+
+/// Check that `bin_dir` is writable.
+fn check_writable(report: &mut Report, bin_dir: &Path) {
+    if !bin_dir.exists() {
+        report.info(format!(
+            "{} does not exist yet, it is created on the first install",
+            bin_dir.display()
+        ));
+        return;
+    }
+    match tempfile::NamedTempFile::new_in(bin_dir) {
+        Ok(_) => report.ok(format!("{} is writable", bin_dir.display())),
+        Err(e) => report.error(format!("{} is not writable: {}", bin_dir.display(), e)),
+    }
+}
+
+/// Check the GitHub token and the remaining rate limit.
+async fn check_github(report: &mut Report, http: &HttpClient) {
+    let mut token = env::var("GITHUB_TOKEN").ok().filter(|t| !t.is_empty());
+    match &token {
+        Some(t) => match github::fetch_rate_limit(http, Some(t)).await {
+            Ok(_) => report.ok("GITHUB_TOKEN is set and valid".to_string()),
+            Err(e) if e.is::<github::InvalidToken>() => {
+                report.error(format!("GITHUB_TOKEN is set but invalid: {}", e));
+                token = None;
+            }
+            Err(e) => report.warning(format!(
+                "GITHUB_TOKEN is set but could not be verified: {}",
+                e
+            )),
+        },
+        None => report.info("GITHUB_TOKEN is not set (60 API requests per hour)".to_string()),
+    }
+
+    match github::fetch_rate_limit(http, token.as_deref()).await {
+        Ok(rate_limit) if rate_limit.remaining == 0 => report.error(format!(
+            "GitHub API rate limit exceeded ({} requests), resets at {}",
+            rate_limit.limit,
+            rate_limit.reset_at.format("%Y-%m-%d %H:%M:%S UTC")
+        )),
+        Ok(rate_limit) => report.ok(format!(
+            "GitHub API rate limit: {}/{} remaining, resets at {}",
+            rate_limit.remaining,
+            rate_limit.limit,
+            rate_limit.reset_at.format("%Y-%m-%d %H:%M:%S UTC")
+        )),
+        Err(e) => report.error(format!("Could not reach the GitHub API: {}", e)),
+    }
+}
+
+/// Check whether pixi is available.
+fn check_pixi(report: &mut Report) {
+    match Command::new("pixi").arg("--version").output() {
+        Ok(output) if output.status.success() => report.ok(format!(
+            "pixi found ({}), pixi-managed apps are skipped",
+            String::from_utf8_lossy(&output.stdout).trim()
+        )),
+        _ => report.info("pixi not found, pixi-managed apps are not detected".to_string()),
+    }
+}
+
+/// Check for backups left behind by an interrupted self-update.
+fn check_self_update_backups(report: &mut Report) -> Result<()> {
+    let current_exe = env::current_exe()?;
+    let (Some(dir), Some(name)) = (current_exe.parent(), current_exe.file_stem()) else {
+        return Ok(());
+    };
+    let name = name.to_string_lossy();
+
+    let mut found = 0;
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        if file_name.starts_with(name.as_ref()) && file_name.ends_with(".old") {
+            report.warning(format!(
+                "Leftover self-update backup {}, it can be deleted",
+                path.display()
+            ));
+            found += 1;
+        }
+    }
+    if found == 0 {
+        report.ok("No leftover self-update backup".to_string());
+    }
+    Ok(())
+}
+
+/// Diagnose common environment problems.
+///
+/// Most "installed but the old version still runs" issues come from `PATH`:
+/// `bin_dir` missing from it, or another copy of a binary found earlier.
+/// Also checks write permission on `bin_dir`, the GitHub token and rate
+/// limit, pixi, and leftover self-update backups.
+///
+/// # Arguments
+/// * `http` - The HTTP client to use.
+/// * `apps` - The configured apps.
+///
+/// # Returns
+/// An error if problems were found, so that the exit code reflects them.
+pub async fn run(http: &HttpClient, apps: &[App]) -> Result<()> {
+    let mut report = Report::default();
+    let bin_dir = crate::get_bin_dir()?;
+
+    check_path(&mut report, &bin_dir);
+    check_shadowed(&mut report, apps, &bin_dir);
+    check_writable(&mut report, &bin_dir);
+    check_github(&mut report, http).await;
+    check_pixi(&mut report);
+    check_self_update_backups(&mut report)?;
+
+    say!();
+    if report.errors > 0 {
+        return Err(anyhow::anyhow!(
+            "{} problem(s) and {} warning(s) found",
+            report.errors,
+            report.warnings
+        ));
+    }
+    if report.warnings > 0 {
+        say!("⚠️  {} warning(s) found", report.warnings);
+    } else {
+        say!("🎉 No problem found");
+    }
+    Ok(())
+}
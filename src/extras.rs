@@ -0,0 +1,186 @@
+use crate::app::Bin;
+use crate::say;
+use anyhow::Result;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Kinds of extra files shipped in release archives.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExtraKind {
+    ManPage,
+    BashCompletion,
+    ZshCompletion,
+    FishCompletion,
+}
+
+impl fmt::Display for ExtraKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtraKind::ManPage => write!(f, "man page"),
+            ExtraKind::BashCompletion => write!(f, "bash completion"),
+            ExtraKind::ZshCompletion => write!(f, "zsh completion"),
+            ExtraKind::FishCompletion => write!(f, "fish completion"),
+        }
+    }
+}
+
+impl ExtraKind {
+    /// Get the directory where files of this kind are installed.
+    ///
+    /// Follows the XDG layout under `$XDG_DATA_HOME` (`~/.local/share` by default).
+    pub fn install_dir(&self) -> Result<PathBuf> {
+        let data_home = crate::get_data_home()?;
+        Ok(match self {
+            ExtraKind::ManPage => data_home.join("man").join("man1"),
+            ExtraKind::BashCompletion => data_home.join("bash-completion").join("completions"),
+            ExtraKind::ZshCompletion => data_home.join("zsh").join("site-functions"),
+            ExtraKind::FishCompletion => data_home.join("fish").join("vendor_completions.d"),
+        })
+    }
+}
+
+/// Identify an extra file from its path and return its kind and installed file name.
+///
+/// Only files related to one of the given binaries are recognised:
+/// * man pages: `{bin}.1` or `{bin}-*.1` (optionally gzipped)
+/// * bash completions: `{bin}.bash` or `bash/{bin}`
+/// * zsh completions: `_{bin}` or `{bin}.zsh`
+/// * fish completions: `{bin}.fish`
+///
+/// # Arguments
+/// * `path` - The path of the file in the extracted archive.
+/// * `bins` - The binaries of the app.
+///
+/// # Returns
+/// The kind of the file and its installed name, or `None` if it is not recognised.
+fn classify(path: &Path, bins: &[Bin]) -> Option<(ExtraKind, String)> {
+    let file_name = path.file_name()?.to_string_lossy().to_string();
+    let parent = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .map(|p| p.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    for bin in bins {
+        for name in [bin.source(), bin.target()] {
+            let target = bin.target();
+            let man_page = file_name.strip_suffix(".gz").unwrap_or(&file_name);
+            if let Some(stem) = man_page.strip_suffix(".1")
+                && (stem == name || stem.starts_with(&format!("{}-", name)))
+            {
+                return Some((ExtraKind::ManPage, file_name.replacen(name, target, 1)));
+            }
+            if file_name == format!("{}.bash", name) || (parent == "bash" && file_name == name) {
+                return Some((ExtraKind::BashCompletion, target.to_string()));
+            }
+            if file_name == format!("_{}", name) || file_name == format!("{}.zsh", name) {
+                return Some((ExtraKind::ZshCompletion, format!("_{}", target)));
+            }
+            if file_name == format!("{}.fish", name) {
+                return Some((ExtraKind::FishCompletion, format!("{}.fish", target)));
+            }
+        }
+    }
+    None
+}
+
+/// Find the man pages and shell completions of an app in an extracted archive.
+///
+/// # Arguments
+/// * `dir` - The directory to search in.
+/// * `bins` - The binaries of the app.
+///
+/// # Returns
+/// A list of `(source path, kind, installed name)` for each recognised file.
+pub fn find_extras(dir: &Path, bins: &[Bin]) -> Result<Vec<(PathBuf, ExtraKind, String)>> {
+    let mut extras = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            extras.extend(find_extras(&path, bins)?);
+        } else if let Some((kind, name)) = classify(&path, bins) {
+            extras.push((path, kind, name));
+        }
+    }
+    Ok(extras)
+}
+
+/// Install the man pages and shell completions found in an extracted archive.
+///
+/// # Arguments
+/// * `dir` - The extracted archive.
+/// * `bins` - The binaries of the app.
+///
+/// # Returns
+/// The list of installed files.
+pub fn install_extras(dir: &Path, bins: &[Bin]) -> Result<Vec<PathBuf>> {
+    let mut installed = Vec::new();
+    for (source, kind, name) in find_extras(dir, bins)? {
+        let install_dir = kind.install_dir()?;
+        fs::create_dir_all(&install_dir)?;
+        let target = install_dir.join(name);
+        fs::copy(&source, &target)?;
+        say!("ℹ️  installed {} to {}", kind, target.display());
+        installed.push(target);
+    }
+    Ok(installed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_extras() {
+        let bins = [
+            Bin::Name("rg".to_string()),
+            Bin::Renamed {
+                from: "fd-find".to_string(),
+                to: "fd".to_string(),
+            },
+        ];
+        let cases = [
+            ("rg-14.1.0/doc/rg.1", Some((ExtraKind::ManPage, "rg.1"))),
+            ("rg/doc/rg.1.gz", Some((ExtraKind::ManPage, "rg.1.gz"))),
+            ("doc/rg-config.1", Some((ExtraKind::ManPage, "rg-config.1"))),
+            ("doc/fd-find.1", Some((ExtraKind::ManPage, "fd.1"))),
+            ("complete/rg.bash", Some((ExtraKind::BashCompletion, "rg"))),
+            (
+                "completions/bash/rg",
+                Some((ExtraKind::BashCompletion, "rg")),
+            ),
+            ("complete/_rg", Some((ExtraKind::ZshCompletion, "_rg"))),
+            (
+                "completions/rg.zsh",
+                Some((ExtraKind::ZshCompletion, "_rg")),
+            ),
+            (
+                "complete/rg.fish",
+                Some((ExtraKind::FishCompletion, "rg.fish")),
+            ),
+            (
+                "autocomplete/fd-find.fish",
+                Some((ExtraKind::FishCompletion, "fd.fish")),
+            ),
+            (
+                "autocomplete/_fd-find",
+                Some((ExtraKind::ZshCompletion, "_fd")),
+            ),
+            ("rg", None),
+            ("bin/rg", None),
+            ("doc/rga.1", None),
+            ("doc/other.1", None),
+            ("complete/other.bash", None),
+            ("README.md", None),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(
+                classify(Path::new(path), &bins),
+                expected.map(|(kind, name)| (kind, name.to_string())),
+                "{}",
+                path
+            );
+        }
+    }
+}
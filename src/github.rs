@@ -1,5 +1,7 @@
+use crate::http::HttpClient;
+use crate::say;
 use anyhow::{Result, anyhow};
-use reqwest::header::{ACCEPT, AUTHORIZATION, USER_AGENT};
+use reqwest::header::{ACCEPT, AUTHORIZATION, HeaderMap};
 use serde::Deserialize;
 use std::{env, fmt};
 
@@ -38,75 +40,196 @@ pub struct Release {
     pub tag_name: String,
     pub html_url: String,
     pub assets: Vec<Asset>,
+    #[serde(default)]
+    pub published_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Title of the release
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Release notes, in markdown
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub prerelease: bool,
     // other fields are available
     // but not super useful for general use
 }
 
-/// Check the GitHub API rate limit and print the remaining limit and reset time.
-pub async fn check_rate_limit(verbose: bool) -> Result<()> {
-    let client = reqwest::Client::new();
-
-    // Check rate limit first
-    let rate_limit_response = client
-        .get("https://api.github.com/rate_limit")
-        .header("User-Agent", "gh-app-installer/0.1.0")
-        .send()
+/// Metadata of a GitHub repository.
+#[derive(Debug, Deserialize)]
+pub struct Repository {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// Fetch the metadata of a GitHub repository given as "owner/repo".
+///
+/// - `http` is the HTTP client to use.
+/// - `repo` must be in the form "owner/repo".
+/// - `token` is an optional GitHub token (useful for private repos and to raise rate limits).
+pub async fn fetch_repository(
+    http: &HttpClient,
+    repo: &str,
+    token: Option<&str>,
+) -> Result<Repository> {
+    let (owner, name) = repo
+        .split_once('/')
+        .ok_or_else(|| anyhow!("invalid repo format, expected owner/repo"))?;
+    let url = format!("https://api.github.com/repos/{owner}/{name}");
+
+    let mut headers = HeaderMap::new();
+    headers.insert(ACCEPT, "application/vnd.github+json".parse()?);
+    if let Some(t) = token {
+        headers.insert(AUTHORIZATION, format!("Bearer {}", t).parse()?);
+    }
+
+    let resp = http.get(&url, headers).await?;
+    match resp.status() {
+        reqwest::StatusCode::OK => Ok(resp.json().await?),
+        reqwest::StatusCode::NOT_FOUND => Err(anyhow!("Repository {} not found", repo)),
+        s => {
+            let text = resp.text().await.unwrap_or_default();
+            Err(anyhow!(
+                "GitHub API returned error {}: {}",
+                s.as_u16(),
+                text
+            ))
+        }
+    }
+}
+
+/// Error returned when the GitHub API rate limit is exhausted.
+#[derive(Debug)]
+pub struct RateLimitExceeded {
+    /// When the rate limit is reset.
+    pub reset_at: chrono::DateTime<chrono::Utc>,
+    /// Human-readable delay until the reset.
+    pub delta: String,
+}
+
+impl fmt::Display for RateLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "🚨 GitHub API rate limit exceeded. Resets at: {} ({})",
+            self.reset_at.format("%Y-%m-%d %H:%M:%S UTC"),
+            self.delta
+        )
+    }
+}
+
+impl std::error::Error for RateLimitExceeded {}
+
+/// Rate limit of the GitHub API for the current client.
+#[derive(Debug)]
+pub struct RateLimit {
+    pub limit: u64,
+    pub remaining: u64,
+    pub reset_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Error returned when GitHub rejects the token.
+#[derive(Debug)]
+pub struct InvalidToken;
+
+impl fmt::Display for InvalidToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "GitHub rejected the token (HTTP 401)")
+    }
+}
+
+impl std::error::Error for InvalidToken {}
+
+/// Fetch the GitHub API rate limit, authenticated with the given token if any.
+///
+/// Querying the rate limit does not count against it, so this also checks
+/// that a token is valid: an `InvalidToken` error is returned for a bad token.
+pub async fn fetch_rate_limit(http: &HttpClient, token: Option<&str>) -> Result<RateLimit> {
+    let mut headers = HeaderMap::new();
+    headers.insert(ACCEPT, "application/vnd.github+json".parse()?);
+    if let Some(t) = token {
+        headers.insert(AUTHORIZATION, format!("Bearer {}", t).parse()?);
+    }
+
+    let resp = http
+        .get("https://api.github.com/rate_limit", headers)
         .await?;
+    match resp.status() {
+        reqwest::StatusCode::OK => {
+            let rate_limit: serde_json::Value = resp.json().await?;
+            let rate = &rate_limit["rate"];
+            Ok(RateLimit {
+                limit: rate["limit"].as_u64().unwrap_or(0),
+                remaining: rate["remaining"].as_u64().unwrap_or(0),
+                reset_at: chrono::DateTime::from_timestamp(rate["reset"].as_i64().unwrap_or(0), 0)
+                    .unwrap_or_default(),
+            })
+        }
+        reqwest::StatusCode::UNAUTHORIZED => Err(InvalidToken.into()),
+        s => Err(anyhow!("GitHub API returned error {}", s.as_u16())),
+    }
+}
+
+/// Check the GitHub API rate limit and print the remaining limit and reset time.
+///
+/// The limit of `GITHUB_TOKEN` is checked when it is set, the anonymous limit
+/// of the IP address otherwise.
+///
+/// Returns a `RateLimitExceeded` error when no request is left.
+pub async fn check_rate_limit(http: &HttpClient, verbose: bool) -> Result<()> {
+    let token = std::env::var("GITHUB_TOKEN").ok();
+    let rate_limit = match fetch_rate_limit(http, token.as_deref()).await {
+        Ok(rate_limit) => rate_limit,
+        Err(e) => {
+            say!("⚠️  Could not check rate limit ({}), proceeding anyway", e);
+            return Ok(());
+        }
+    };
 
-    if !rate_limit_response.status().is_success() {
-        println!("⚠️  Could not check rate limit, proceeding anyway");
-        Ok(())
+    let reset_datetime = rate_limit.reset_at;
+    let time_until_reset = reset_datetime.signed_duration_since(chrono::Utc::now());
+    let delta_str = if time_until_reset.num_seconds() <= 0 {
+        "should reset now".to_string()
+    } else if time_until_reset.num_hours() > 0 {
+        format!("in {}hrs", time_until_reset.num_hours())
+    } else if time_until_reset.num_minutes() > 0 {
+        format!("in {}min", time_until_reset.num_minutes())
     } else {
-        let rate_limit_text = rate_limit_response.text().await?;
-        match serde_json::from_str::<serde_json::Value>(&rate_limit_text) {
-            Ok(rate_limit) => {
-                let remaining = rate_limit["rate"]["remaining"].as_u64().unwrap_or(1);
-                let reset_time = rate_limit["rate"]["reset"].as_u64().unwrap_or(0);
-                let reset_datetime =
-                    chrono::DateTime::from_timestamp(reset_time as i64, 0).unwrap_or_default();
-                let now = chrono::Utc::now();
-                let time_until_reset = reset_datetime.signed_duration_since(now);
-
-                let delta_str = if time_until_reset.num_seconds() <= 0 {
-                    "should reset now".to_string()
-                } else if time_until_reset.num_hours() > 0 {
-                    format!("in {}hrs", time_until_reset.num_hours())
-                } else if time_until_reset.num_minutes() > 0 {
-                    format!("in {}min", time_until_reset.num_minutes())
-                } else {
-                    "very soon".to_string()
-                };
-
-                if remaining > 0 {
-                    if verbose {
-                        println!("✅  Rate limit remaining: {}", remaining);
-                        println!(
-                            "ℹ️  Rate limit reset at: {} {}",
-                            reset_datetime.format("%Y-%m-%d %H:%M:%S UTC"),
-                            delta_str
-                        );
-                    }
-                    return Ok(());
-                }
+        "very soon".to_string()
+    };
 
-                return Err(anyhow::anyhow!(
-                    "🚨 GitHub API rate limit exceeded. Resets at: {} ({})",
-                    reset_datetime.format("%Y-%m-%d %H:%M:%S UTC"),
-                    delta_str
-                ));
-            }
-            _ => Err(anyhow::anyhow!("Unexpected response from GitHub API")),
+    if rate_limit.remaining > 0 {
+        if verbose {
+            say!("✅  Rate limit remaining: {}", rate_limit.remaining);
+            say!(
+                "ℹ️  Rate limit reset at: {} {}",
+                reset_datetime.format("%Y-%m-%d %H:%M:%S UTC"),
+                delta_str
+            );
         }
+        return Ok(());
+    }
+
+    Err(RateLimitExceeded {
+        reset_at: reset_datetime,
+        delta: delta_str,
     }
+    .into())
 }
 
 /// Fetch the assets of the latest GitHub Release for a repository given as "owner/repo".
 ///
+/// - `http` is the HTTP client to use.
 /// - `repo` must be in the form "owner/repo".
 /// - `token` is an optional GitHub token (useful for private repos and to raise rate limits).
 ///
 /// Returns a Release or an Error if the repository has no Release (GitHub returns 404 for "no release").
-pub async fn fetch_latest_release(repo: &str, token: Option<&str>) -> Result<Release> {
+pub async fn fetch_latest_release(
+    http: &HttpClient,
+    repo: &str,
+    token: Option<&str>,
+) -> Result<Release> {
     // check repo format
     let mut parts = repo.splitn(2, '/');
     let owner = parts.next().ok_or_else(|| anyhow!("invalid repo format"))?;
@@ -119,18 +242,15 @@ pub async fn fetch_latest_release(repo: &str, token: Option<&str>) -> Result<Rel
         name = name
     );
 
-    // set the client
-    let client = reqwest::Client::new();
-    let mut req = client
-        .get(&url)
-        .header(USER_AGENT, "gh_release_assets")
-        .header(ACCEPT, "application/vnd.github+json");
+    // set the headers
+    let mut headers = HeaderMap::new();
+    headers.insert(ACCEPT, "application/vnd.github+json".parse()?);
     if let Some(t) = token {
-        req = req.header(AUTHORIZATION, format!("Bearer {}", t));
+        headers.insert(AUTHORIZATION, format!("Bearer {}", t).parse()?);
     }
 
     // send request
-    let resp = req.send().await?;
+    let resp = http.get(&url, headers).await?;
 
     // check response
     match resp.status() {
@@ -154,30 +274,80 @@ pub async fn fetch_latest_release(repo: &str, token: Option<&str>) -> Result<Rel
     }
 }
 
-impl Release {
-    pub async fn fetch_latest(repo: &str, token: Option<&str>) -> Self {
-        let release = fetch_latest_release(repo, token).await;
-        if release.is_ok() {
-            let release = release.unwrap();
-            Self {
-                tag_name: release.tag_name,
-                html_url: release.html_url,
-                assets: release.assets,
-            }
-        } else {
-            Self {
-                tag_name: String::new(),
-                html_url: String::new(),
-                assets: Vec::new(),
-            }
+/// Number of releases in a page of the GitHub releases API.
+pub const RELEASES_PER_PAGE: usize = 100;
+
+/// Fetch the most recent releases of a repository given as "owner/repo", newest first.
+///
+/// - `http` is the HTTP client to use.
+/// - `repo` must be in the form "owner/repo".
+/// - `token` is an optional GitHub token (useful for private repos and to raise rate limits).
+///
+/// Only the first page of 100 releases is fetched.
+pub async fn fetch_releases(
+    http: &HttpClient,
+    repo: &str,
+    token: Option<&str>,
+) -> Result<Vec<Release>> {
+    fetch_releases_page(http, repo, token, 1).await
+}
+
+/// Fetch a page of the releases of a repository given as "owner/repo", newest first.
+///
+/// - `http` is the HTTP client to use.
+/// - `repo` must be in the form "owner/repo".
+/// - `token` is an optional GitHub token (useful for private repos and to raise rate limits).
+/// - `page` is the number of the page of `RELEASES_PER_PAGE` releases, starting at 1.
+pub async fn fetch_releases_page(
+    http: &HttpClient,
+    repo: &str,
+    token: Option<&str>,
+    page: u32,
+) -> Result<Vec<Release>> {
+    let (owner, name) = repo
+        .split_once('/')
+        .ok_or_else(|| anyhow!("invalid repo format, expected owner/repo"))?;
+    let url = format!(
+        "https://api.github.com/repos/{owner}/{name}/releases?per_page={RELEASES_PER_PAGE}&page={page}"
+    );
+
+    let mut headers = HeaderMap::new();
+    headers.insert(ACCEPT, "application/vnd.github+json".parse()?);
+    if let Some(t) = token {
+        headers.insert(AUTHORIZATION, format!("Bearer {}", t).parse()?);
+    }
+
+    let resp = http.get(&url, headers).await?;
+    match resp.status() {
+        reqwest::StatusCode::OK => Ok(resp.json().await?),
+        s => {
+            let text = resp.text().await.unwrap_or_default();
+            Err(anyhow!(
+                "GitHub API returned error {}: {}",
+                s.as_u16(),
+                text
+            ))
         }
     }
+}
+
+impl Release {
+    pub async fn fetch_latest(http: &HttpClient, repo: &str, token: Option<&str>) -> Self {
+        fetch_latest_release(http, repo, token)
+            .await
+            .unwrap_or_else(|_| Self::default())
+    }
 
     pub fn default() -> Self {
         Self {
             tag_name: String::new(),
             html_url: String::new(),
             assets: Vec::new(),
+            published_at: None,
+            name: None,
+            body: None,
+            draft: false,
+            prerelease: false,
         }
     }
 }
@@ -195,15 +365,11 @@ impl Platform {
             arch: env::consts::ARCH.to_string(),
         }
     }
-
-    pub fn to_string(&self) -> String {
-        format!("{}-{}", self.os, self.arch)
-    }
 }
 
 impl fmt::Display for Platform {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.to_string())
+        write!(f, "{}-{}", self.os, self.arch)
     }
 }
 
@@ -267,6 +433,22 @@ pub fn asset_matcher(
     matcher: Option<&PlatformMatcher>,
     current_platform: Option<&Platform>,
 ) -> Result<()> {
+    match_reason(asset_name, matcher, current_platform)
+        .map(|_| ())
+        .ok_or_else(|| anyhow::anyhow!("No match found"))
+}
+
+/// Explain why an asset matches the current platform.
+///
+/// Same rules as `asset_matcher`.
+///
+/// # Returns
+/// A description of the rule that matched, or `None` if the asset does not match.
+pub fn match_reason(
+    asset_name: &str,
+    matcher: Option<&PlatformMatcher>,
+    current_platform: Option<&Platform>,
+) -> Option<String> {
     let matcher = match matcher {
         Some(m) => m,
         None => &PlatformMatcher::default(),
@@ -283,35 +465,41 @@ pub fn asset_matcher(
     let name = asset_name.to_lowercase();
     // direct match
     if name.contains(&current_platform.os) && name.contains(&current_platform.arch) {
-        return Ok(());
+        return Some(format!(
+            "name contains \"{}\" and \"{}\"",
+            current_platform.os, current_platform.arch
+        ));
     }
     // Try to find matches using aliases
     if let (Some(os_aliases), Some(arch_aliases)) = (os_aliases, arch_aliases) {
         for os_alias in os_aliases {
             for arch_alias in arch_aliases {
                 if name.contains(os_alias) && name.contains(arch_alias) {
-                    return Ok(());
+                    return Some(format!(
+                        "name contains OS alias \"{}\" and arch alias \"{}\"",
+                        os_alias, arch_alias
+                    ));
                 }
             }
         }
     } else if let Some(os_aliases) = os_aliases {
         for os_alias in os_aliases {
             if name.contains(os_alias) {
-                return Ok(());
+                return Some(format!("name contains OS alias \"{}\"", os_alias));
             }
         }
     } else if let Some(arch_aliases) = arch_aliases {
         for arch_alias in arch_aliases {
             if name.contains(arch_alias) {
-                return Ok(());
+                return Some(format!("name contains arch alias \"{}\"", arch_alias));
             }
         }
     }
-    Err(anyhow::anyhow!("No match found"))
+    None
 }
 
 pub fn find_platform_assets<'a>(
-    assets: &'a Vec<Asset>,
+    assets: &'a [Asset],
     matcher: Option<&PlatformMatcher>,
     current_platform: Option<&Platform>,
 ) -> Result<Vec<&'a Asset>> {
@@ -327,11 +515,11 @@ pub fn find_platform_assets<'a>(
 
     let mut matched_assets: Vec<_> = assets
         .iter()
-        .filter(|asset| asset_matcher(&asset.name, Some(&matcher), Some(&current_platform)).is_ok())
+        .filter(|asset| asset_matcher(&asset.name, Some(matcher), Some(current_platform)).is_ok())
         .map(|asset| {
             (
                 asset,
-                calculate_asset_priority(&asset, Some(&current_platform)),
+                calculate_asset_priority(asset, Some(current_platform)),
             )
         })
         .collect();
@@ -339,7 +527,7 @@ pub fn find_platform_assets<'a>(
     if matched_assets.is_empty() {
         return Err(anyhow::anyhow!("No match found"));
     } else if matched_assets.len() > 1 {
-        matched_assets.sort_by(|a, b| b.1.cmp(&a.1));
+        matched_assets.sort_by_key(|a| std::cmp::Reverse(a.1));
     }
 
     let sorted_assets: Vec<&Asset> = matched_assets.iter().map(|(asset, _)| *asset).collect();
@@ -348,13 +536,21 @@ pub fn find_platform_assets<'a>(
 }
 
 fn calculate_asset_priority(asset: &Asset, current_platform: Option<&Platform>) -> i32 {
+    priority_rules(asset, current_platform)
+        .iter()
+        .map(|(score, _)| score)
+        .sum()
+}
+
+/// Get the priority rules that apply to an asset, with their score.
+fn priority_rules(asset: &Asset, current_platform: Option<&Platform>) -> Vec<(i32, &'static str)> {
     // provide default matcher and platform if not provided
     let current_platform = match current_platform {
         Some(p) => p,
         None => &Platform::current(),
     };
     let name = asset.name.to_lowercase();
-    let mut priority: i32 = 0;
+    let mut rules = Vec::new();
 
     // priority for usual archives (instead of deb, rpm, apk etc.)
     if name.ends_with(".tar.gz")
@@ -362,26 +558,58 @@ fn calculate_asset_priority(asset: &Asset, current_platform: Option<&Platform>)
         || name.ends_with(".tgz")
         || name.ends_with(".zip")
     {
-        priority += 1000;
+        rules.push((1000, "usual archive format"));
     }
 
     // priority for platform-specific coherence
     if current_platform.os == "linux" && name.contains("android") {
-        priority -= 2000;
+        rules.push((-2000, "android build on linux"));
     }
     if current_platform.os == "macos" && name.contains("ios") {
-        priority -= 2000;
+        rules.push((-2000, "ios build on macos"));
     }
 
     // priority for coherent architecture
     if current_platform.arch == "x86_64" && (name.contains("arm") || name.contains("aarch64")) {
-        priority -= 1000;
+        rules.push((-1000, "arm build on x86_64"));
     }
 
     // prefer musl over gnu for linux
     if current_platform.os == "linux" && name.contains("musl") {
-        priority += 500;
+        rules.push((500, "musl build on linux"));
     }
 
-    priority
+    rules
+}
+
+/// Result of the asset selection rules for one asset.
+pub struct AssetEvaluation<'a> {
+    pub asset: &'a Asset,
+    /// Why the asset matches the current platform, if it does
+    pub matched: Option<String>,
+    /// Total priority score
+    pub score: i32,
+    /// Priority rules that fired, with their score
+    pub rules: Vec<(i32, &'static str)>,
+}
+
+/// Evaluate every asset of a release against the current platform.
+///
+/// Gives the details behind `find_platform_assets`: the match result of
+/// `asset_matcher` and the priority score of each asset.
+pub fn evaluate_assets(assets: &[Asset]) -> Vec<AssetEvaluation<'_>> {
+    let matcher = PlatformMatcher::default();
+    let current_platform = Platform::current();
+    assets
+        .iter()
+        .map(|asset| {
+            let rules = priority_rules(asset, Some(&current_platform));
+            AssetEvaluation {
+                asset,
+                matched: match_reason(&asset.name, Some(&matcher), Some(&current_platform)),
+                score: rules.iter().map(|(score, _)| score).sum(),
+                rules,
+            }
+        })
+        .collect()
 }
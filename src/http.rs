@@ -0,0 +1,258 @@
+use crate::Settings;
+use crate::progress;
+use crate::say;
+use anyhow::{Context, Result};
+use indicatif::ProgressBar;
+use reqwest::StatusCode;
+use reqwest::header::{CONTENT_RANGE, HeaderMap, RANGE};
+use std::fs;
+use std::io::{Seek, Write};
+use std::time::Duration;
+
+/// User-Agent sent with every request.
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// HTTP client with timeouts and retries.
+///
+/// A single client is built at startup and shared by all requests so that
+/// connections are pooled. Transient failures (connection errors, timeouts, 5xx and 429
+/// responses) are retried with an exponential backoff.
+pub struct HttpClient {
+    client: reqwest::Client,
+    read_timeout: Duration,
+    retries: u32,
+}
+
+/// Check whether an error is worth retrying.
+///
+/// Connection errors, timeouts, interrupted bodies, 5xx and 429 responses are transient.
+fn is_transient(err: &anyhow::Error) -> bool {
+    if let Some(e) = err.downcast_ref::<reqwest::Error>() {
+        return e.is_timeout()
+            || e.is_connect()
+            || e.is_body()
+            || e.status().is_some_and(is_retryable);
+    }
+    err.is::<tokio::time::error::Elapsed>()
+}
+
+/// Check whether a response status is worth retrying.
+fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Discard a partial download to start it over.
+fn restart(file: &mut fs::File, written: &mut u64, bar: &mut Option<ProgressBar>) -> Result<()> {
+    file.set_len(0)?;
+    file.rewind()?;
+    *written = 0;
+    if let Some(bar) = bar {
+        bar.set_position(0);
+    }
+    Ok(())
+}
+
+/// Get the first byte of a `Content-Range` header (`bytes 100-199/200`).
+fn content_range_start(response: &reqwest::Response) -> Option<u64> {
+    let range = response.headers().get(CONTENT_RANGE)?.to_str().ok()?;
+    let (start, _) = range.strip_prefix("bytes ")?.split_once('-')?;
+    start.trim().parse().ok()
+}
+
+impl HttpClient {
+    /// Create a client from the global settings.
+    ///
+    /// Proxies are read from the environment (`HTTPS_PROXY`, `HTTP_PROXY`,
+    /// `NO_PROXY`) unless `proxy` is set in the settings, and the certificates
+    /// of `ca_bundle` are trusted in addition to the built-in roots.
+    pub fn new(settings: &Settings) -> Result<Self> {
+        let mut builder = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .connect_timeout(Duration::from_secs(settings.connect_timeout));
+
+        if let Some(proxy) = &settings.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .with_context(|| format!("Invalid proxy URL: {}", proxy))?
+                .no_proxy(reqwest::NoProxy::from_env());
+            builder = builder.proxy(proxy);
+        }
+
+        if let Some(ca_bundle) = &settings.ca_bundle {
+            let pem = fs::read(ca_bundle)
+                .with_context(|| format!("Failed to read CA bundle: {}", ca_bundle.display()))?;
+            let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+                .with_context(|| format!("Invalid CA bundle: {}", ca_bundle.display()))?;
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+
+        let client = builder.build()?;
+        Ok(Self {
+            client,
+            read_timeout: Duration::from_secs(settings.read_timeout),
+            retries: settings.retries,
+        })
+    }
+
+    /// Wait before the given retry attempt (exponential backoff starting at 1s).
+    async fn backoff(&self, attempt: u32, err: &anyhow::Error) {
+        let delay = Duration::from_secs(1 << (attempt - 1).min(5));
+        say!(
+            "⚠️  {} (retrying in {}s, attempt {}/{})",
+            err,
+            delay.as_secs(),
+            attempt,
+            self.retries
+        );
+        tokio::time::sleep(delay).await;
+    }
+
+    /// Send a single GET request, waiting at most `read_timeout` for the response.
+    async fn send(&self, url: &str, headers: &HeaderMap) -> Result<reqwest::Response> {
+        let request = self.client.get(url).headers(headers.clone()).send();
+        Ok(tokio::time::timeout(self.read_timeout, request).await??)
+    }
+
+    /// Send a GET request with the given headers.
+    ///
+    /// Transient errors, 5xx and 429 responses are retried. After the last retry,
+    /// the response is returned as is and the caller is in charge of checking its status.
+    ///
+    /// # Arguments
+    /// * `url` - The URL to fetch.
+    /// * `headers` - The headers to send with the request.
+    ///
+    /// # Returns
+    /// A `Result` containing the response or the last transient error.
+    pub async fn get(&self, url: &str, headers: HeaderMap) -> Result<reqwest::Response> {
+        let mut attempt = 0;
+        loop {
+            let err = match self.send(url, &headers).await {
+                Ok(response) if is_retryable(response.status()) && attempt < self.retries => {
+                    anyhow::anyhow!("{} returned HTTP {}", url, response.status())
+                }
+                Ok(response) => return Ok(response),
+                Err(e) if is_transient(&e) && attempt < self.retries => e,
+                Err(e) => return Err(e),
+            };
+            attempt += 1;
+            self.backoff(attempt, &err).await;
+        }
+    }
+
+    /// Stream the content of the given URL into a file.
+    ///
+    /// The response is written chunk by chunk instead of being buffered in
+    /// memory, and a progress bar labelled with `label` reports the transfer.
+    /// Interrupted transfers are retried and resumed with an HTTP Range request
+    /// when the server supports it.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL of the file to download.
+    /// * `label` - The label of the progress bar.
+    /// * `file` - The file to write the content to.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` which is `Ok` if the download was successful, or an error.
+    pub async fn download_to_file(
+        &self,
+        url: &str,
+        label: &str,
+        file: &mut fs::File,
+    ) -> Result<()> {
+        let mut written: u64 = 0;
+        let mut bar: Option<ProgressBar> = None;
+        let mut attempt = 0;
+
+        loop {
+            match self
+                .try_download(url, label, file, &mut written, &mut bar)
+                .await
+            {
+                Ok(()) => break,
+                Err(e) if is_transient(&e) && attempt < self.retries => {
+                    attempt += 1;
+                    self.backoff(attempt, &e).await;
+                }
+                Err(e) => {
+                    if let Some(bar) = &bar {
+                        bar.abandon();
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        file.flush()?;
+        if let Some(bar) = bar {
+            bar.finish_and_clear();
+        }
+        Ok(())
+    }
+
+    /// Download (the rest of) a file, resuming after `written` bytes if possible.
+    ///
+    /// The download starts over when the server ignores the range request,
+    /// answers with a range that does not start at `written`, or cannot
+    /// satisfy it (416).
+    async fn try_download(
+        &self,
+        url: &str,
+        label: &str,
+        file: &mut fs::File,
+        written: &mut u64,
+        bar: &mut Option<ProgressBar>,
+    ) -> Result<()> {
+        let mut response = loop {
+            let mut headers = HeaderMap::new();
+            if *written > 0 {
+                headers.insert(RANGE, format!("bytes={}-", written).parse()?);
+            }
+            let response = self.send(url, &headers).await?;
+
+            match response.status() {
+                StatusCode::PARTIAL_CONTENT if content_range_start(&response) == Some(*written) => {
+                    break response;
+                }
+                StatusCode::PARTIAL_CONTENT | StatusCode::RANGE_NOT_SATISFIABLE if *written > 0 => {
+                    // Unusable range: request the whole file again
+                    restart(file, written, bar)?;
+                }
+                StatusCode::PARTIAL_CONTENT => {
+                    return Err(anyhow::anyhow!(
+                        "Failed to download {}: unexpected partial content",
+                        url
+                    ));
+                }
+                s if s.is_success() => {
+                    // The server ignored the range request: start over
+                    if *written > 0 {
+                        restart(file, written, bar)?;
+                    }
+                    break response;
+                }
+                s if is_retryable(s) => {
+                    return Err(response.error_for_status().unwrap_err().into());
+                }
+                s => {
+                    return Err(anyhow::anyhow!("Failed to download {}: HTTP {}", url, s));
+                }
+            }
+        };
+
+        let total = response.content_length().map(|len| len + *written);
+        let bar = bar.get_or_insert_with(|| progress::download_bar(label, total));
+        bar.set_position(*written);
+
+        while let Some(chunk) = tokio::time::timeout(self.read_timeout, response.chunk()).await?? {
+            file.write_all(&chunk)?;
+            *written += chunk.len() as u64;
+            bar.inc(chunk.len() as u64);
+        }
+
+        Ok(())
+    }
+}
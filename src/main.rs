@@ -1,23 +1,78 @@
 mod app;
+mod changelog;
+mod config_edit;
+mod doctor;
+mod extras;
 mod github;
+mod http;
+mod output;
+mod progress;
+mod state;
+mod version;
 use anyhow::{Context, Result};
-use app::{App, AppStatus, InstallationMethod, extract_version_from_string};
-use clap::{Parser, Subcommand};
-use github::{Release, check_rate_limit};
+use app::{App, AppStatus, InstallationMethod, Layout, extract_version_from_string};
+use clap::{Parser, Subcommand, ValueEnum};
+use github::{Asset, RateLimitExceeded, Release, check_rate_limit};
+use http::HttpClient;
+use output::{Action, AppReport, OutputFormat};
 use regex::Regex;
 use semver::Version;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use state::{AppState, Hold, State};
 use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
 use std::{env, fs};
 use tempfile::TempDir;
+use version::{UpdatePolicy, VersionScheme};
 
 // app.yaml format =================================================
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Config {
+    #[serde(default)]
+    pub settings: Settings,
     pub apps: Vec<App>,
+    /// Path of the file the configuration was loaded from
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+/// Global settings shared by all apps.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Maximum time to establish a connection, in seconds
+    pub connect_timeout: u64,
+    /// Maximum time to wait for data from the server, in seconds
+    pub read_timeout: u64,
+    /// Number of retries for transient network errors, 5xx and 429 responses
+    pub retries: u32,
+    /// Proxy URL for all requests (defaults to `HTTPS_PROXY`/`HTTP_PROXY`, honouring `NO_PROXY`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Additional PEM bundle of CA certificates to trust (e.g. a TLS-intercepting proxy)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<PathBuf>,
+    /// Number of installed versions kept in the store for rollbacks
+    pub keep_versions: usize,
+    /// Which updates are applied, unless an app sets its own policy
+    pub update: UpdatePolicy,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            connect_timeout: 10,
+            read_timeout: 30,
+            retries: 3,
+            proxy: None,
+            ca_bundle: None,
+            keep_versions: 3,
+            update: UpdatePolicy::Major,
+        }
+    }
 }
 
 /// check for a configuration file in order or priority:
@@ -45,7 +100,7 @@ fn locate_config_file(config_file: &str) -> Result<PathBuf> {
 
     for path in config_file_paths {
         if path.exists() {
-            println!("ℹ️  Using configuration from {}", path.display());
+            say!("ℹ️  Using configuration from {}", path.display());
             return Ok(path);
         }
     }
@@ -65,24 +120,22 @@ fn locate_config_file(config_file: &str) -> Result<PathBuf> {
 /// or `Err` if there was an error.
 async fn create_sample_config_file(config_file: &str) -> Result<()> {
     let sample_config = Config {
+        settings: Settings::default(),
+        path: PathBuf::from(config_file),
         apps: vec![
             App {
                 name: "dust".to_string(),
                 bin: "dust".to_string(),
                 repo: Some("bootandy/dust".to_string()),
                 description: Some("A disk usage analyzer".to_string()),
-                install_command: None,
-                update_command: None,
-                version_command: None,
+                ..App::default()
             },
             App {
                 name: "bat".to_string(),
                 bin: "bat".to_string(),
                 description: Some("A cat clone with syntax highlighting".to_string()),
                 repo: Some("sharkdp/bat".to_string()),
-                install_command: None,
-                update_command: None,
-                version_command: None,
+                ..App::default()
             },
             App {
                 name: "uv".to_string(),
@@ -91,14 +144,14 @@ async fn create_sample_config_file(config_file: &str) -> Result<()> {
                 install_command: Some("{download(https://astral.sh/uv/install.sh, /tmp/uv-install.sh)} && sh /tmp/uv-install.sh --bin-dir {bin_dir} --yes".to_string()),
                 update_command: Some("{bin_path} self update".to_string()),
                 description: Some("A fast python package manager".to_string()),
-                version_command: None,
+                ..App::default()
             }, ],
         };
 
     let yaml = serde_yaml::to_string(&sample_config)?;
     let config_sample_file = PathBuf::from(config_file);
     fs::write(&config_sample_file, yaml)?;
-    println!(
+    say!(
         "📝 Created sample config file: {}",
         config_sample_file.display()
     );
@@ -120,59 +173,197 @@ async fn create_sample_config_file(config_file: &str) -> Result<()> {
 /// A `Result` which is `Ok` if the configuration was loaded successfully,
 /// or `Err` if there was an error.
 async fn load_config(config_file: &str) -> Result<Config> {
-    let config_path = locate_config_file(config_file);
-
-    // if the config file does not exist, create a sample config file
-    if config_path.is_err() {
-        create_sample_config_file(config_file).await?;
-    }
-    let config_path = config_path.unwrap();
+    let config_path = match locate_config_file(config_file) {
+        Ok(path) => path,
+        // if the config file does not exist, create a sample config file
+        Err(_) => {
+            create_sample_config_file(config_file).await?;
+            PathBuf::from(config_file)
+        }
+    };
 
     let content = fs::read_to_string(&config_path)
         .with_context(|| format!("Failed to read config file: {}", config_path.display()))?;
 
-    let config: Config =
+    let mut config: Config =
         serde_yaml::from_str(&content).with_context(|| "Failed to parse YAML config")?;
+    config.path = config_path;
+    for app in &config.apps {
+        app.validate()?;
+    }
 
     Ok(config)
 }
 
+/// Fetch the latest release of the given application.
+///
+/// For apps with `tag_prefix` or `tag_regex`, this is the most recent stable
+/// release whose tag belongs to the app, since the repository may publish
+/// several products. Otherwise it is the latest release of the repository.
+/// Up to `MAX_RELEASE_PAGES` pages of releases are searched for the tag.
+///
+/// # Arguments
+///
+/// * `http` - The HTTP client to use.
+/// * `app` - The application, with a repository.
+///
+/// # Returns
+///
+/// A `Result` containing the release, or an error if none was found.
+async fn fetch_app_release(http: &HttpClient, app: &App) -> Result<Release> {
+    let token = env::var("GITHUB_TOKEN").ok();
+    if !app.filters_tags() {
+        return github::fetch_latest_release(http, app.get_repo(), token.as_deref()).await;
+    }
+    for page in 1..=MAX_RELEASE_PAGES {
+        let releases =
+            github::fetch_releases_page(http, app.get_repo(), token.as_deref(), page).await?;
+        let last_page = releases.len() < github::RELEASES_PER_PAGE;
+        if let Some(release) = releases.into_iter().find(|release| {
+            !release.draft
+                && !release.prerelease
+                && app.version_from_tag(&release.tag_name).is_some()
+        }) {
+            return Ok(release);
+        }
+        if last_page {
+            break;
+        }
+    }
+    Err(anyhow::anyhow!(
+        "No release found with a tag matching {}",
+        app.name
+    ))
+}
+
+/// Maximum number of pages of releases searched for the tags of an app.
+const MAX_RELEASE_PAGES: u32 = 10;
+
+/// Find the highest stable release that an update policy allows.
+///
+/// # Arguments
+///
+/// * `releases` - The releases of the application.
+/// * `app` - The application.
+/// * `policy` - The update policy of the application.
+/// * `current` - The installed version.
+///
+/// # Returns
+///
+/// The release and its version, if one is newer than `current` and allowed.
+fn highest_allowed_release(
+    releases: Vec<Release>,
+    app: &App,
+    policy: UpdatePolicy,
+    current: &str,
+) -> Option<(Release, String)> {
+    let scheme = app.version_scheme;
+    releases
+        .into_iter()
+        .filter(|release| !release.draft && !release.prerelease)
+        .filter_map(|release| {
+            let version = app.version_from_tag(&release.tag_name)?;
+            Some((release, version))
+        })
+        .filter(|(_, version)| {
+            scheme
+                .compare(version, current)
+                .is_some_and(|ordering| ordering.is_gt())
+                && policy.allows(scheme, current, version)
+        })
+        .max_by(|(_, a), (_, b)| scheme.compare(a, b).unwrap_or(std::cmp::Ordering::Equal))
+}
+
+/// Find the highest stable release of the given application that its
+/// update policy allows, starting from the installed version.
+///
+/// Pages of releases are fetched until one reaches a version not newer than
+/// the installed one, or after `MAX_RELEASE_PAGES` pages.
+///
+/// # Arguments
+///
+/// * `http` - The HTTP client to use.
+/// * `app` - The application, with a repository.
+/// * `policy` - The update policy of the application.
+/// * `current` - The installed version.
+///
+/// # Returns
+///
+/// A `Result` containing the release and its version, if one was found.
+async fn fetch_allowed_release(
+    http: &HttpClient,
+    app: &App,
+    policy: UpdatePolicy,
+    current: &str,
+) -> Result<Option<(Release, String)>> {
+    let token = env::var("GITHUB_TOKEN").ok();
+    let mut releases = Vec::new();
+    for page in 1..=MAX_RELEASE_PAGES {
+        let page_releases =
+            github::fetch_releases_page(http, app.get_repo(), token.as_deref(), page).await?;
+        let last_page = page_releases.len() < github::RELEASES_PER_PAGE;
+        let reached_current = page_releases.iter().any(|release| {
+            app.version_from_tag(&release.tag_name)
+                .is_some_and(|version| {
+                    app.version_scheme
+                        .compare(&version, current)
+                        .is_some_and(|ordering| ordering.is_le())
+                })
+        });
+        releases.extend(page_releases);
+        if last_page || reached_current {
+            break;
+        }
+    }
+    Ok(highest_allowed_release(releases, app, policy, current))
+}
+
 /// Get the status and release information for the given application.
 ///
 /// This function fetches the latest release information from GitHub for the given application.
 /// It also checks the rate limit and retrieves the repository information.
 ///
+/// When the update policy of the application does not allow the latest
+/// version, it is recorded as held back and the highest allowed release
+/// is used instead (or the installed version, if there is none).
+///
 /// # Arguments
 ///
+/// * `http` - The HTTP client to use.
+/// * `settings` - The global settings.
 /// * `app` - The application for which to fetch the status and release information.
 ///
 /// # Returns
 ///
 /// A `Result` containing a tuple with the application status and the latest release information.
-async fn get_app_status_and_release(app: &App, debug: bool) -> Result<(AppStatus, Release)> {
+async fn get_app_status_and_release(
+    http: &HttpClient,
+    settings: &Settings,
+    app: &App,
+    debug: bool,
+) -> Result<(AppStatus, Release)> {
     let mut status = AppStatus::new(app, debug);
 
     // check online assets and versions
-    check_rate_limit(false).await?;
+    check_rate_limit(http, false).await?;
 
-    let release_info: Release;
+    let mut release_info: Release;
     let repo = status.app.get_repo();
 
     // get version from repo is any
     if repo.is_empty() {
         release_info = Release::default();
         // check if version_command is present
-        if app.version_command.is_some() {
-            let command = app.version_command.as_ref().unwrap();
-            let processed_command = process_template(command, app, "").await?;
-            println!(
+        if let Some(command) = &app.version_command {
+            let processed_command = process_template(http, command, app, "").await?;
+            say!(
                 "   ⚙️ Getting latest version for {} with command\n\t {} ",
                 app.name,
                 processed_command.trim()
             );
             let output = Command::new("sh")
                 .arg("-c")
-                .arg(format!("{}", processed_command))
+                .arg(&processed_command)
                 .output()?;
             if !output.status.success() {
                 let stderr = String::from_utf8_lossy(&output.stderr);
@@ -185,18 +376,50 @@ async fn get_app_status_and_release(app: &App, debug: bool) -> Result<(AppStatus
                 // merge stdout into a string
                 let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
                 // parse stdout into a version
-                if let Some(version) = extract_version_from_string(&stdout) {
-                    println!("   ⚙️ Got {}", version.clone());
-                    status.set_latest_version(version);
-                } else {
-                    println!("  ❓ Could not parse version from {}", stdout);
-                }
+                let version = app.version_scheme.extract(&stdout).ok_or_else(|| {
+                    anyhow::anyhow!("Could not parse a version from '{}'", stdout)
+                })?;
+                say!("   ⚙️ Got {}", version.clone());
+                status.set_latest_version(version);
             }
         }
     } else {
-        release_info = Release::fetch_latest(repo, env::var("GITHUB_TOKEN").ok().as_deref()).await;
-        if let Some(latest_version) = extract_version_from_string(&release_info.tag_name) {
-            status.set_latest_version(latest_version);
+        release_info = fetch_app_release(http, app).await?;
+        let latest_version = app
+            .version_from_tag(&release_info.tag_name)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Could not find a version in the tag '{}' of {}",
+                    release_info.tag_name,
+                    repo
+                )
+            })?;
+        status.set_latest_version(latest_version);
+    }
+
+    // Keep updates within the range allowed by the update policy
+    let policy = app.update_policy(settings);
+    if let (Some(current), Some(latest)) = (
+        status.current_version.clone(),
+        status.latest_version.clone(),
+    ) && status.is_version_update_needed()
+        && !policy.allows(app.version_scheme, &current, &latest)
+    {
+        status.held_back_version = Some(latest);
+        let allowed = if app.get_repo().is_empty() || policy == UpdatePolicy::None {
+            None
+        } else {
+            fetch_allowed_release(http, app, policy, &current).await?
+        };
+        match allowed {
+            Some((release, version)) => {
+                release_info = release;
+                status.set_latest_version(version);
+            }
+            None => {
+                release_info = Release::default();
+                status.set_latest_version(current);
+            }
         }
     }
 
@@ -214,27 +437,29 @@ async fn get_app_status_and_release(app: &App, debug: bool) -> Result<(AppStatus
 /// # Returns
 ///
 /// A `Result` containing a tuple with the application status and the latest release information.
-async fn get_thisapp_status_and_release() -> Result<(AppStatus, Release)> {
+async fn get_thisapp_status_and_release(http: &HttpClient) -> Result<(AppStatus, Release)> {
     let mut this_app_status = AppStatus {
         pixi_managed: Some(false),
         current_version: Some(env!("CARGO_PKG_VERSION").to_string()),
         latest_version: None,
+        held_back_version: None,
+        managed_path: None,
+        resolved_path: None,
         app: App {
             name: "rs-gh-app".to_string(),
             repo: Some("mfouesneau/rs-gh-app".to_string()),
             bin: "rs-gh-app".to_string(),
-            install_command: None,
-            update_command: None,
             description: Some("A command-line tool for managing GitHub applications".to_string()),
-            version_command: None,
+            ..App::default()
         },
     };
 
     // check online assets and versions
-    check_rate_limit(false).await?;
+    check_rate_limit(http, false).await?;
     let repo = this_app_status.app.get_repo();
 
-    let release_info = Release::fetch_latest(repo, env::var("GITHUB_TOKEN").ok().as_deref()).await;
+    let release_info =
+        Release::fetch_latest(http, repo, env::var("GITHUB_TOKEN").ok().as_deref()).await;
 
     if let Some(latest_version) = extract_version_from_string(&release_info.tag_name) {
         this_app_status.set_latest_version(latest_version);
@@ -252,11 +477,108 @@ enum Commands {
         /// Preview what would be done without actually installing
         #[arg(long)]
         dry_run: bool,
+        /// Output format (human messages go to stderr with json and yaml)
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        output: OutputFormat,
+        /// Do not show the release notes nor ask for confirmation before updates
+        #[arg(long)]
+        no_changelog: bool,
     },
     /// Check versions without installing
     Check {
         /// Application name to check (checks all if not specified)
         app_name: Option<String>,
+        /// Output format (human messages go to stderr with json and yaml)
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        output: OutputFormat,
+        /// Exit with 10 if updates are available, 11 if some apps failed to be checked and 12 if rate limited
+        #[arg(long)]
+        exit_code: bool,
+        /// Show the release notes between the installed and the latest version
+        #[arg(long)]
+        changelog: bool,
+    },
+    /// List configured applications and their local installation status
+    List {
+        /// Only show installed applications
+        #[arg(long, conflicts_with = "missing")]
+        installed: bool,
+        /// Only show applications that are not installed
+        #[arg(long)]
+        missing: bool,
+        /// Column to sort the table by
+        #[arg(long, value_enum, default_value_t = SortKey::Name)]
+        sort: SortKey,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        output: OutputFormat,
+    },
+    /// Uninstall an application
+    Uninstall {
+        /// Application name to uninstall
+        app_name: String,
+        /// Preview what would be removed without actually uninstalling
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Diagnose environment problems (PATH, permissions, GitHub token, rate limit)
+    Doctor,
+    /// Show the latest release of an application and how its asset is selected
+    Info {
+        /// Application name to inspect
+        app_name: String,
+    },
+    /// Add an application from a GitHub repository to the configuration file
+    Add {
+        /// GitHub repository, as owner/repo
+        repo: String,
+        /// Name of the application (defaults to the repository name)
+        #[arg(long)]
+        name: Option<String>,
+        /// Binary of the application (defaults to the executable found in the latest release)
+        #[arg(long)]
+        bin: Option<String>,
+        /// Print the proposed entry without modifying the configuration file
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Remove an application from the configuration file
+    Remove {
+        /// Application name to remove
+        app_name: String,
+    },
+    /// Set a field of an application in the configuration file
+    Set {
+        /// Application name to edit
+        app_name: String,
+        /// Field to set (e.g. description, repo, extras, keep_versions)
+        key: String,
+        /// New value, parsed as YAML (`null` removes the field)
+        value: String,
+    },
+    /// Switch an application back to its previously installed version
+    Rollback {
+        /// Application name to roll back
+        app_name: String,
+        /// Preview what would be done without actually switching versions
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Freeze an application so that `install` leaves it untouched
+    Hold {
+        /// Application name to hold
+        app_name: String,
+        /// Release the hold after this date (YYYY-MM-DD, included) or RFC 3339 time
+        #[arg(long)]
+        until: Option<String>,
+        /// Why the application is held, shown by `check` and `install`
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// Release the hold of an application
+    Unhold {
+        /// Application name to release
+        app_name: String,
     },
     /// Update this tool to the latest version
     SelfUpdate {
@@ -266,6 +588,17 @@ enum Commands {
     },
 }
 
+/// Columns of the `list` table that can be sorted on
+#[derive(Clone, Copy, ValueEnum)]
+enum SortKey {
+    Name,
+    Bin,
+    Repo,
+    Method,
+    Version,
+    Checked,
+}
+
 #[derive(Parser)]
 #[command(name = "rs-app-installer")]
 #[command(about = "Install and update applications")]
@@ -302,17 +635,54 @@ fn get_bin_dir() -> Result<PathBuf> {
     }
 }
 
+/// Get the directory where the installed versions of an app are kept
+///
+/// Assumes `~/.local/share/rs-gh-app/{name}`.
+fn get_app_store_dir(app: &App) -> Result<PathBuf> {
+    Ok(get_data_home()?.join("rs-gh-app").join(&app.name))
+}
+
+/// Get the name of the store directory of a version.
+///
+/// Path separators are replaced by `_`, as is a leading `.` (which marks the
+/// temporary directories of the store), so that `release/2024-05-01` is
+/// kept in `release_2024-05-01`.
+fn store_dir_name(version: &str) -> String {
+    let name = version.replace(['/', '\\'], "_");
+    match name.strip_prefix('.') {
+        Some(rest) => format!("_{}", rest),
+        None if name.is_empty() => "_".to_string(),
+        None => name,
+    }
+}
+
+/// Get the base directory for user data files
+///
+/// Assumes `~/.local/share` or provided by environment variable `XDG_DATA_HOME`.
+///
+/// Returns an error if the directory cannot be determined.
+fn get_data_home() -> Result<PathBuf> {
+    match std::env::var("XDG_DATA_HOME") {
+        Ok(data_home) if !data_home.is_empty() => Ok(PathBuf::from(data_home)),
+        _ => {
+            let home = dirs::home_dir()
+                .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
+            Ok(home.join(".local").join("share"))
+        }
+    }
+}
+
 /// Extract a tar.gz archive to the specified destination path.
 ///
 /// # Arguments
-/// * `bytes` - The bytes of the tar.gz archive
+/// * `archive_path` - The path of the tar.gz archive
 /// * `dest_path` - The destination path to extract the archive to
 ///
 /// # Returns
 /// * `Ok(())` - If the extraction was successful
 /// * `Err(Error)` - If the extraction failed
-fn extract_tar_gz(bytes: &[u8], dest_path: &std::path::Path) -> Result<()> {
-    let tar = flate2::read::GzDecoder::new(bytes);
+fn extract_tar_gz(archive_path: &Path, dest_path: &Path) -> Result<()> {
+    let tar = flate2::read::GzDecoder::new(fs::File::open(archive_path)?);
     let mut archive = tar::Archive::new(tar);
     archive.unpack(dest_path)?;
     Ok(())
@@ -321,14 +691,14 @@ fn extract_tar_gz(bytes: &[u8], dest_path: &std::path::Path) -> Result<()> {
 /// Extract a tar archive to the specified destination path.
 ///
 /// # Arguments
-/// * `bytes` - The bytes of the tar archive
+/// * `archive_path` - The path of the tar archive
 /// * `dest_path` - The destination path to extract the archive to
 ///
 /// # Returns
 /// * `Ok(())` - If the extraction was successful
 /// * `Err(Error)` - If the extraction failed
-fn extract_tar(bytes: &[u8], dest_path: &std::path::Path) -> Result<()> {
-    let mut archive = tar::Archive::new(bytes);
+fn extract_tar(archive_path: &Path, dest_path: &Path) -> Result<()> {
+    let mut archive = tar::Archive::new(fs::File::open(archive_path)?);
     archive.unpack(dest_path)?;
     Ok(())
 }
@@ -336,15 +706,14 @@ fn extract_tar(bytes: &[u8], dest_path: &std::path::Path) -> Result<()> {
 /// Extract a zip archive to the specified destination path.
 ///
 /// # Arguments
-/// * `bytes` - The bytes of the zip archive
+/// * `archive_path` - The path of the zip archive
 /// * `dest_path` - The destination path to extract the archive to
 ///
 /// # Returns
 /// * `Ok(())` - If the extraction was successful
 /// * `Err(Error)` - If the extraction failed
-fn extract_zip(bytes: &[u8], dest_path: &std::path::Path) -> Result<()> {
-    let cursor = std::io::Cursor::new(bytes);
-    let mut archive = zip::ZipArchive::new(cursor)?;
+fn extract_zip(archive_path: &Path, dest_path: &Path) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(archive_path)?)?;
 
     for i in 0..archive.len() {
         let mut file = archive.by_index(i)?;
@@ -353,10 +722,10 @@ fn extract_zip(bytes: &[u8], dest_path: &std::path::Path) -> Result<()> {
         if file.name().ends_with('/') {
             fs::create_dir_all(&outpath)?;
         } else {
-            if let Some(p) = outpath.parent() {
-                if !p.exists() {
-                    fs::create_dir_all(p)?;
-                }
+            if let Some(p) = outpath.parent()
+                && !p.exists()
+            {
+                fs::create_dir_all(p)?;
             }
             let mut outfile = fs::File::create(&outpath)?;
             std::io::copy(&mut file, &mut outfile)?;
@@ -389,17 +758,16 @@ fn find_binary_in_extracted(dir: &std::path::Path, bin_name: &str) -> Result<Pat
             let path = entry.path();
 
             if path.is_file() {
-                if let Some(name) = path.file_name() {
-                    if name.to_string_lossy() == bin_name
-                        || name.to_string_lossy() == format!("{}.exe", bin_name)
-                    {
-                        return Ok(Some(path));
-                    }
-                }
-            } else if path.is_dir() {
-                if let Some(found) = search_recursive(&path, bin_name)? {
-                    return Ok(Some(found));
+                if let Some(name) = path.file_name()
+                    && (name.to_string_lossy() == bin_name
+                        || name.to_string_lossy() == format!("{}.exe", bin_name))
+                {
+                    return Ok(Some(path));
                 }
+            } else if path.is_dir()
+                && let Some(found) = search_recursive(&path, bin_name)?
+            {
+                return Ok(Some(found));
             }
         }
         Ok(None)
@@ -433,6 +801,16 @@ fn filter_apps(apps: &[App], app_name: Option<String>) -> Result<Vec<App>> {
     }
 }
 
+/// Select the asset of the given release that would be installed on the current platform.
+///
+/// Same choice as `get_best_url`, without printing anything.
+fn select_asset(release: &Release) -> Option<&Asset> {
+    github::find_platform_assets(&release.assets, None, None)
+        .ok()?
+        .into_iter()
+        .find(|asset| asset.browser_download_url.is_some())
+}
+
 /// Get the best URL for the given release.
 ///
 /// Returns the URL of the first asset that matches the current platform and has a valid download URL.
@@ -453,9 +831,9 @@ fn get_best_url(release: &Release) -> Result<String> {
             "❌ No assets found for the current platform"
         ));
     } else if matched_assets.len() > 1 {
-        println!("⚠️  Multiple assets matching the current platform");
+        say!("⚠️  Multiple assets matching the current platform");
         matched_assets.iter().for_each(|asset| {
-            println!("  - {}", asset);
+            say!("  - {}", asset);
         });
         let selected: Vec<_> = matched_assets
             .iter()
@@ -465,7 +843,7 @@ fn get_best_url(release: &Release) -> Result<String> {
         if selected.is_empty() {
             return Err(anyhow::anyhow!("❌ No assets with download URL found."));
         } else {
-            println!("⚠️  Defaulting to the first asset ({})", selected[0].name);
+            say!("⚠️  Defaulting to the first asset ({})", selected[0].name);
             url = selected[0].browser_download_url.as_ref().unwrap().clone();
         }
     } else {
@@ -486,90 +864,105 @@ fn get_best_url(release: &Release) -> Result<String> {
 ///
 /// # Arguments
 ///
+/// * `http` - The HTTP client to use.
 /// * `url` - The URL of the file to download.
 /// * `dest_path` - The destination path where the file should be saved.
 ///
 /// # Returns
 ///
 /// A `Result` containing the path of the downloaded file or an error.
-async fn download_file(url: &str, dest_path: &str) -> Result<String> {
-    let client = reqwest::Client::new();
-    let response = client
-        .get(url)
-        .header("User-Agent", "gh-app-installer/0.1.0")
-        .send()
-        .await?;
-
-    if !response.status().is_success() {
-        return Err(anyhow::anyhow!(
-            "Failed to download {}: HTTP {}",
-            url,
-            response.status()
-        ));
-    }
-
-    let bytes = response.bytes().await?;
-
+async fn download_file(http: &HttpClient, url: &str, dest_path: &str) -> Result<String> {
     // Create parent directories if they don't exist
-    if let Some(parent) = std::path::Path::new(dest_path).parent() {
+    if let Some(parent) = Path::new(dest_path).parent() {
         fs::create_dir_all(parent)?;
     }
 
-    fs::write(dest_path, bytes)?;
+    let mut file = fs::File::create(dest_path)?;
+    let label = Path::new(dest_path)
+        .file_name()
+        .map_or(dest_path.to_string(), |n| n.to_string_lossy().to_string());
+    http.download_to_file(url, &label, &mut file).await?;
 
     Ok(dest_path.to_string())
 }
 
-async fn download_and_extract(url: &str, temp_path: &Path) -> Result<()> {
-    // Download and extract
-    let client = reqwest::Client::new();
-    let response = client
-        .get(url)
-        .header("User-Agent", "gh-app-installer/0.1.0")
-        .send()
+/// Download an archive and extract it into the given directory.
+///
+/// The archive is streamed to a temporary file before extraction.
+///
+/// # Arguments
+///
+/// * `http` - The HTTP client to use.
+/// * `url` - The URL of the archive to download.
+/// * `temp_path` - The directory to extract the archive to.
+/// * `label` - The label of the progress bar (usually the app name).
+///
+/// # Returns
+///
+/// A `Result` containing the SHA-256 of the archive, or an error.
+async fn download_and_extract(
+    http: &HttpClient,
+    url: &str,
+    temp_path: &Path,
+    label: &str,
+) -> Result<String> {
+    // Download
+    let mut archive = tempfile::NamedTempFile::new()?;
+    http.download_to_file(url, label, archive.as_file_mut())
         .await?;
+    let sha256 = sha256_file(archive.path())?;
 
-    if !response.status().is_success() {
-        return Err(anyhow::anyhow!(
-            "Failed to download update: HTTP {}",
-            response.status()
-        ));
-    }
-
-    let bytes = response.bytes().await?;
-    //
     // Extract archive based on URL extension
-    println!("ℹ️  Temporary folder {}", temp_path.display());
+    say!("ℹ️  Temporary folder {}", temp_path.display());
     if url.ends_with(".tar.gz") || url.ends_with(".tgz") {
-        extract_tar_gz(&bytes, temp_path)?;
+        extract_tar_gz(archive.path(), temp_path)?;
     } else if url.ends_with(".tar") {
-        extract_tar(&bytes, temp_path)?;
+        extract_tar(archive.path(), temp_path)?;
     } else if url.ends_with(".zip") {
-        extract_zip(&bytes, temp_path)?;
+        extract_zip(archive.path(), temp_path)?;
     } else {
         return Err(anyhow::anyhow!("Unsupported archive format"));
     }
     // show extracted files
-    println!("ℹ️  Extracted files:");
+    say!("ℹ️  Extracted files:");
     for entry in fs::read_dir(temp_path)? {
         let entry = entry?;
         if let Some(name) = entry.path().file_name() {
-            println!("    - {}", name.to_string_lossy());
+            say!("    - {}", name.to_string_lossy());
         }
     }
 
-    Ok(())
+    Ok(sha256)
+}
+
+/// Compute the SHA-256 of a file.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file.
+///
+/// # Returns
+///
+/// A `Result` containing the hex-encoded hash, or an error.
+fn sha256_file(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
 }
 
 /// Update gh-app-installer to the latest version
 ///
 /// Returns an error if the update fails.
 ///
-async fn self_update(dry_run: bool) -> Result<()> {
-    println!("🔍 Checking for updates to gh-app-installer...");
-    let (status, release) = get_thisapp_status_and_release().await?;
+async fn self_update(http: &HttpClient, dry_run: bool) -> Result<()> {
+    say!("🔍 Checking for updates to gh-app-installer...");
+    let (status, release) = get_thisapp_status_and_release(http).await?;
 
-    println!("{}", status);
+    say!("{}", status);
 
     // Parse versions for comparison
     let latest_version = status.latest_version.unwrap();
@@ -582,25 +975,27 @@ async fn self_update(dry_run: bool) -> Result<()> {
     // Check if the latest version is newer than the current version
     if latest_version_parsed <= current_version_parsed {
         if latest_version_parsed == current_version_parsed {
-            println!(
+            say!(
                 "✅ gh-app-installer is already at the latest version (v{})",
                 current_version
             );
         } else {
-            println!(
+            say!(
                 "ℹ️  Local version (v{}) is newer than the latest release (v{})",
-                current_version, latest_version
+                current_version,
+                latest_version
             );
         }
         return Ok(());
     }
-    println!(
+    say!(
         "🆕 Updating gh-app-installer v{} -> v{}",
-        current_version, latest_version
+        current_version,
+        latest_version
     );
 
     if dry_run {
-        println!("🔄 [DRY RUN] Would update binary");
+        say!("🔄 [DRY RUN] Would update binary");
     }
 
     // Get current executable path
@@ -628,17 +1023,17 @@ async fn self_update(dry_run: bool) -> Result<()> {
     let url = get_best_url(&release)?;
 
     if dry_run {
-        println!("   📥 [DRY RUN] Would Downloading from {}", url);
+        say!("   📥 [DRY RUN] Would Downloading from {}", url);
         return Ok(());
     } else {
-        println!("   📥  Downloading from {}", url);
+        say!("   📥  Downloading from {}", url);
     }
 
     let temp_dir = TempDir::new()?;
     let temp_path = temp_dir.path();
 
     // Download and extract
-    download_and_extract(&url, &temp_path).await?;
+    download_and_extract(http, &url, temp_path, "rs-gh-app").await?;
 
     // Find the new binary
     let new_binary_path = find_binary_in_extracted(temp_path, "rs-gh-app")
@@ -646,7 +1041,7 @@ async fn self_update(dry_run: bool) -> Result<()> {
         .context("Could not find updated binary in downloaded archive")?;
 
     // Replace current binary and set permissions
-    println!("   🔄 Replacing current binary...");
+    say!("   🔄 Replacing current binary...");
 
     let backup_path: PathBuf;
 
@@ -680,149 +1075,801 @@ async fn self_update(dry_run: bool) -> Result<()> {
         let _ = fs::remove_file(&backup_path);
     }
 
-    println!(
+    say!(
         "✅ Successfully updated gh-app-installer to v{}",
         latest_version
     );
-    println!("🎉 Run the command again to use the new version");
+    say!("🎉 Run the command again to use the new version");
 
     Ok(())
 }
 
+/// Overall result of `check`, reported through the exit code with `--exit-code`.
+///
+/// Variants are ordered by precedence: the worst result of all apps wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum CheckOutcome {
+    UpToDate,
+    UpdatesAvailable,
+    Failed,
+    RateLimited,
+}
+
+impl CheckOutcome {
+    /// Get the outcome of an app that failed to be checked.
+    fn from_error(err: &anyhow::Error) -> Self {
+        if err.is::<RateLimitExceeded>() {
+            CheckOutcome::RateLimited
+        } else {
+            CheckOutcome::Failed
+        }
+    }
+
+    /// Get the process exit code of the outcome.
+    fn exit_code(self) -> i32 {
+        match self {
+            CheckOutcome::UpToDate => 0,
+            CheckOutcome::UpdatesAvailable => 10,
+            CheckOutcome::Failed => 11,
+            CheckOutcome::RateLimited => 12,
+        }
+    }
+}
+
 /**
  * Check the status of the given apps.
  *
  * If `stop_on_error` is `true`, the function will stop checking apps if an error occurs.
  * If `stop_on_error` is `false`, the function will continue checking apps even if an error occurs.
+ * With a structured `format`, one record per app is written to stdout.
+ * With `changelog`, the release notes of outdated apps are printed.
+ * Apps held with the `hold` command are listed separately and do not
+ * count as having updates available.
+ *
+ * Returns the overall outcome of the check.
  */
-async fn check_apps(apps: Vec<App>, stop_on_error: bool, debug: bool) -> Result<()> {
+async fn check_apps(
+    http: &HttpClient,
+    settings: &Settings,
+    apps: Vec<App>,
+    changelog: bool,
+    stop_on_error: bool,
+    debug: bool,
+    format: OutputFormat,
+) -> Result<CheckOutcome> {
+    let mut outcome = CheckOutcome::UpToDate;
+    let mut reports = Vec::new();
+    let mut state = State::load()?;
     for app in apps {
-        match get_app_status_and_release(&app, debug).await {
-            Ok((status, _)) => {
-                println!("{}", status);
+        let mut report = AppReport::new(&app.name);
+        match get_app_status_and_release(http, settings, &app, debug).await {
+            Ok((status, release)) => {
+                // Remember when the app was last checked, saved once all apps are checked
+                state.apps.entry(app.name.clone()).or_default().last_checked =
+                    Some(chrono::Utc::now());
+                say!("{}", status);
+                report.set_status(&status);
+                report.asset = select_asset(&release).map(|asset| asset.name.clone());
+                report.hold = state.active_hold(&app.name).cloned();
+                if report.update_needed && report.hold.is_none() {
+                    outcome = outcome.max(CheckOutcome::UpdatesAvailable);
+                    if changelog
+                        && let (Some(current_version), Some(latest_version)) =
+                            (&status.current_version, &status.latest_version)
+                        && !app.get_repo().is_empty()
+                        && let Err(e) =
+                            changelog::print_changelog(http, &app, current_version, latest_version)
+                                .await
+                    {
+                        say!("⚠️  Could not fetch the release notes: {}", e);
+                    }
+                }
+                reports.push(report);
             }
             Err(e) => {
-                println!("❌ Failed to get status for {}: {}", &app.name.clone(), e);
+                say!("❌ Failed to get status for {}: {}", &app.name.clone(), e);
+                outcome = outcome.max(CheckOutcome::from_error(&e));
+                report.error = Some(e.to_string());
+                reports.push(report);
                 if stop_on_error {
+                    state.save()?;
+                    output::print_records(format, &reports)?;
                     return Err(e);
                 }
             }
         }
     }
-    Ok(())
+    state.save()?;
+    let held: Vec<&AppReport> = reports
+        .iter()
+        .filter(|report| report.hold.is_some())
+        .collect();
+    if !held.is_empty() {
+        say!("\n⏸️  Held with `hold`:");
+        for report in held {
+            if let Some(hold) = &report.hold {
+                say!("   {} held {}", report.name, hold.describe());
+            }
+        }
+    }
+    output::print_records(format, &reports)?;
+    Ok(outcome)
 }
 
-/// Download an app from the given URL and install it.
+/// Local installation status of an app, as shown by `list`.
+#[derive(Debug, Serialize)]
+struct ListEntry {
+    name: String,
+    bin: String,
+    repo: Option<String>,
+    method: InstallationMethod,
+    installed_version: Option<String>,
+    install_path: Option<PathBuf>,
+    pixi_managed: bool,
+    last_checked: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ListEntry {
+    /// Get the cells of the entry in the `list` table.
+    fn cells(&self) -> [String; 8] {
+        [
+            self.name.clone(),
+            self.bin.clone(),
+            self.repo.clone().unwrap_or_default(),
+            self.method.to_string(),
+            self.installed_version
+                .clone()
+                .unwrap_or_else(|| "-".to_string()),
+            self.install_path
+                .as_ref()
+                .map_or("-".to_string(), |path| path.display().to_string()),
+            if self.pixi_managed { "yes" } else { "no" }.to_string(),
+            self.last_checked.map_or("never".to_string(), |checked| {
+                checked
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            }),
+        ]
+    }
+}
+
+/// Print a table of the given apps and their local installation status.
 ///
-/// Sets the permissions to executable if necessary.
-async fn download_and_install(app: &App, url: &str) -> Result<()> {
-    let bin_dir = get_bin_dir()?;
+/// Only local information is used (install receipts, binaries and pixi), no API call is made.
+///
+/// # Arguments
+///
+/// * `apps` - The apps to list.
+/// * `installed` - Whether to only list installed apps.
+/// * `missing` - Whether to only list apps that are not installed.
+/// * `sort` - The column to sort by.
+/// * `format` - The output format.
+fn list_apps(
+    apps: Vec<App>,
+    installed: bool,
+    missing: bool,
+    sort: SortKey,
+    format: OutputFormat,
+) -> Result<()> {
+    let state = State::load()?;
 
-    let temp_dir = TempDir::new()?;
-    let temp_path = temp_dir.path();
+    let mut entries = Vec::new();
+    for app in apps {
+        let receipt = state.apps.get(&app.name);
+        let install_path = app::installed_path(&app.bin);
+        let is_installed = install_path.is_some();
+        if (installed && !is_installed) || (missing && is_installed) {
+            continue;
+        }
+        let installed_version = receipt
+            .and_then(|receipt| receipt.version.clone())
+            .or_else(|| {
+                install_path.as_ref().and_then(|path| {
+                    app::get_current_version_with_debug(&path.to_string_lossy(), &app, false)
+                })
+            });
+
+        entries.push(ListEntry {
+            name: app.name.clone(),
+            bin: app.bin.clone(),
+            repo: app.repo.clone(),
+            method: app.installation_method(),
+            installed_version,
+            install_path,
+            pixi_managed: app::check_pixi_managed(&app.bin),
+            last_checked: receipt.and_then(|receipt| receipt.last_checked),
+        });
+    }
 
-    // Download and extract
-    download_and_extract(&url, &temp_path).await?;
+    entries.sort_by(|a, b| {
+        let order = match sort {
+            SortKey::Name => a.name.cmp(&b.name),
+            SortKey::Bin => a.bin.cmp(&b.bin),
+            SortKey::Repo => a.repo.cmp(&b.repo),
+            SortKey::Method => a.method.to_string().cmp(&b.method.to_string()),
+            SortKey::Version => a.installed_version.cmp(&b.installed_version),
+            SortKey::Checked => a.last_checked.cmp(&b.last_checked),
+        };
+        order.then_with(|| a.name.cmp(&b.name))
+    });
 
-    // Find and move binary
-    let binary_path = find_binary_in_extracted(temp_path, &app.bin)?;
-    let target_path = bin_dir.join(&app.bin);
+    if format != OutputFormat::Text {
+        return output::print_records(format, &entries);
+    }
 
-    fs::copy(&binary_path, &target_path)?;
-    println!(
-        "ℹ️  moved {} to {}",
-        binary_path.display(),
-        target_path.display()
-    );
+    let rows: Vec<[String; 8]> = entries.iter().map(|entry| entry.cells()).collect();
+    let header = [
+        "NAME", "BIN", "REPO", "METHOD", "VERSION", "PATH", "PIXI", "CHECKED",
+    ];
+    let mut widths = header.map(|h| h.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let format_row = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
 
-    // Make executable on Unix
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = fs::metadata(&target_path)?.permissions();
-        perms.set_mode(0o755);
-        fs::set_permissions(&target_path, perms)?;
+    say!("{}", format_row(header.to_vec()));
+    for row in &rows {
+        say!("{}", format_row(row.iter().map(|c| c.as_str()).collect()));
     }
 
     Ok(())
 }
 
-/// Process a template string by replacing placeholders with actual values.
+/// Download an app from the given URL and install it.
 ///
-/// # Arguments
-/// * `template` - The template string to process.
-/// * `app` - The application information.
-/// * `version` - The version of the application.
+/// Each installed version is kept in a versioned store under
+/// `~/.local/share/rs-gh-app/{name}/{version}` and the binaries in `bin_dir`
+/// are symlinks to the active version. With the binary layout only the
+/// binaries are kept, with the directory layout the whole extracted archive is.
 ///
-/// # Returns
-/// A `Result` containing the processed template string or an error.
+/// All the binaries of the app are found before anything is switched, so that
+/// a partial archive never leaves a mix of old and new binaries.
 ///
-/// # Examples
-/// ```
-/// let template = "Hello, {{name}} {{version}} on {{os}}-{{arch}}!";
-/// ```
-async fn process_template(template: &str, app: &App, version: &str) -> Result<String> {
-    let bin_dir = get_bin_dir()?;
-    let bin_path = bin_dir.join(&app.bin);
-    let app_path = std::env::current_dir()?;
-
-    // get system info
-    let raw_os = std::env::consts::OS;
-    let raw_arch = std::env::consts::ARCH;
+/// Returns the install receipt with the downloaded asset and the installed files.
+async fn download_and_install(
+    http: &HttpClient,
+    app: &App,
+    url: &str,
+    version: &str,
+    keep_versions: usize,
+) -> Result<AppState> {
+    let store_dir = get_app_store_dir(app)?;
+    fs::create_dir_all(&store_dir)?;
+
+    // Extract next to the final location to be moved in place
+    let temp_dir = tempfile::Builder::new()
+        .prefix(".download")
+        .tempdir_in(&store_dir)?;
+    let temp_path = temp_dir.path();
 
-    let (normalized_os, normalized_arch, suffix) = match (raw_os, raw_arch) {
-        ("linux", "x86_64") => ("linux", "x86_64", "x86_64-unknown-linux-gnu"),
-        ("linux", "aarch64") => ("linux", "aarch64", "aarch64-unknown-linux-gnu"),
-        ("macos", "x86_64") => ("darwin", "x86_64", "x86_64-apple-darwin"),
-        ("macos", "aarch64") => ("darwin", "aarch64", "aarch64-apple-darwin"),
-        ("windows", "x86_64") => ("windows", "x86_64", "x86_64-pc-windows-msvc"),
-        _ => {
-            return Err(anyhow::anyhow!(
-                "Unsupported platform: {}-{}",
-                raw_os,
-                raw_arch
-            ));
+    // Download and extract
+    let sha256 = download_and_extract(http, url, temp_path, &app.name).await?;
+
+    let version_dir = store_dir.join(store_dir_name(version));
+    let staging_dir = match app.layout {
+        Layout::Binary => {
+            // Find all binaries and only keep them
+            let staging_dir = tempfile::Builder::new()
+                .prefix(".staging")
+                .tempdir_in(&store_dir)?;
+            for bin in app.binaries() {
+                let binary_path = find_binary_in_extracted(temp_path, bin.source())?;
+                stage_binary(&binary_path, &staging_dir.path().join(bin.target()))?;
+            }
+            staging_dir.keep()
         }
+        Layout::Directory => temp_path.to_path_buf(),
     };
 
-    let mut variables = HashMap::new();
-    variables.insert("name".to_string(), app.name.clone());
-    variables.insert("bin".to_string(), app.bin.clone());
-    variables.insert("version".to_string(), version.to_string());
-    variables.insert("os".to_string(), normalized_os.to_string());
-    variables.insert("arch".to_string(), normalized_arch.to_string());
-    variables.insert("raw_os".to_string(), raw_os.to_string());
-    variables.insert("raw_arch".to_string(), raw_arch.to_string());
-    variables.insert("suffix".to_string(), suffix.to_string());
-    variables.insert("bin_dir".to_string(), bin_dir.display().to_string());
-    variables.insert("bin_path".to_string(), bin_path.display().to_string());
-    variables.insert("app_path".to_string(), app_path.display().to_string());
-
-    let mut result = template.to_string();
-
-    // Process download functions first
-    result = process_download_functions(&result).await?;
+    // Reinstalling a version keeps the previous copy aside until the new one is linked
+    let previous_dir = if version_dir.exists() {
+        let previous_dir = store_dir.join(format!(".previous-{}", store_dir_name(version)));
+        if previous_dir.exists() {
+            fs::remove_dir_all(&previous_dir)?;
+        }
+        fs::rename(&version_dir, &previous_dir)?;
+        Some(previous_dir)
+    } else {
+        None
+    };
+    let linked = fs::rename(&staging_dir, &version_dir)
+        .map_err(anyhow::Error::from)
+        // Switch bin_dir to the new version
+        .and_then(|_| link_version(app, &version_dir));
+    let mut installed = match linked {
+        Ok(installed) => {
+            if let Some(previous_dir) = previous_dir {
+                fs::remove_dir_all(previous_dir)?;
+            }
+            installed
+        }
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging_dir);
+            let _ = fs::remove_dir_all(&version_dir);
+            if let Some(previous_dir) = previous_dir {
+                fs::rename(previous_dir, &version_dir)?;
+            }
+            return Err(e);
+        }
+    };
 
-    // Then process regular variables
-    for (key, value) in variables {
-        result = result.replace(&format!("{{{}}}", key), &value);
+    // Man pages and shell completions
+    if app.extras {
+        let extracted_path = match app.layout {
+            Layout::Binary => temp_path,
+            Layout::Directory => &version_dir,
+        };
+        installed.extend(extras::install_extras(extracted_path, &app.binaries())?);
     }
 
-    Ok(result)
+    prune_versions(app, &store_dir, &version_dir, keep_versions)?;
+    installed.push(store_dir);
+
+    Ok(AppState {
+        asset_url: Some(url.to_string()),
+        sha256: Some(sha256),
+        files: installed,
+        ..AppState::default()
+    })
 }
 
-/// Process download functions in the template.
+/// Point the binaries of an app in `bin_dir` to the given version of the store.
+///
+/// The links are staged next to their destination and only moved into place
+/// once every binary of the version was found.
+///
+/// # Arguments
+/// * `app` - The app to link.
+/// * `version_dir` - The directory of the version in the store.
+///
+/// # Returns
+/// The list of links in `bin_dir`.
+fn link_version(app: &App, version_dir: &Path) -> Result<Vec<PathBuf>> {
+    let bin_dir = get_bin_dir()?;
+
+    // Find all binaries before touching bin_dir
+    let mut binaries = Vec::new();
+    for bin in app.binaries() {
+        let binary_path = match app.layout {
+            Layout::Binary => find_binary_in_extracted(version_dir, bin.target())?,
+            Layout::Directory => find_binary_in_extracted(version_dir, bin.source())?,
+        };
+        binaries.push((binary_path, bin_dir.join(bin.target())));
+    }
+
+    // Stage links in bin_dir
+    let mut staged = Vec::new();
+    for (binary_path, target_path) in &binaries {
+        let staging_path = bin_dir.join(format!(
+            ".{}.tmp",
+            target_path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+        ));
+        let result = stage_symlink(binary_path, &staging_path);
+        staged.push(staging_path);
+        if let Err(e) = result {
+            staged.iter().for_each(|path| {
+                let _ = fs::remove_file(path);
+            });
+            return Err(e);
+        }
+    }
+
+    // Move them into place
+    let mut installed = Vec::new();
+    for ((binary_path, target_path), staging_path) in binaries.iter().zip(&staged) {
+        fs::rename(staging_path, target_path)?;
+        say!(
+            "ℹ️  linked {} to {}",
+            target_path.display(),
+            binary_path.display()
+        );
+        installed.push(target_path.clone());
+    }
+
+    Ok(installed)
+}
+
+/// List the versions of an app in the store, newest first.
+///
+/// Versions are ordered with the version scheme of the app. Directories whose
+/// name does not follow the scheme come last, most recently installed first.
+///
+/// # Arguments
+/// * `app` - The app whose versions are listed.
+/// * `store_dir` - The store directory of the app.
+///
+/// # Returns
+/// The version directories, newest first.
+fn list_store_versions(app: &App, store_dir: &Path) -> Result<Vec<PathBuf>> {
+    if !store_dir.exists() {
+        return Ok(vec![]);
+    }
+    let scheme = app.version_scheme;
+    let mut versions = Vec::new();
+    let mut others = Vec::new();
+    for entry in fs::read_dir(store_dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !path.is_dir() || name.starts_with('.') {
+            continue;
+        }
+        // A version can be compared with itself only if it follows the scheme,
+        // and `string` versions have no order
+        if scheme != VersionScheme::String && scheme.compare(&name, &name).is_some() {
+            versions.push((name, path));
+        } else {
+            others.push((entry.metadata()?.modified()?, path));
+        }
+    }
+    versions.sort_by(|a, b| {
+        scheme
+            .compare(&b.0, &a.0)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    others.sort_by_key(|v| std::cmp::Reverse(v.0));
+    Ok(versions
+        .into_iter()
+        .map(|(_, path)| path)
+        .chain(others.into_iter().map(|(_, path)| path))
+        .collect())
+}
+
+/// Remove the oldest versions of an app from the store.
+///
+/// # Arguments
+/// * `app` - The app to prune.
+/// * `store_dir` - The store directory of the app.
+/// * `active_dir` - The directory of the active version, which is always kept.
+/// * `keep_versions` - The number of versions to keep.
+fn prune_versions(
+    app: &App,
+    store_dir: &Path,
+    active_dir: &Path,
+    keep_versions: usize,
+) -> Result<()> {
+    let mut kept = 1;
+    for path in list_store_versions(app, store_dir)? {
+        if path == active_dir {
+            continue;
+        }
+        if kept < keep_versions.max(1) {
+            kept += 1;
+        } else {
+            say!("ℹ️  removing old version {}", path.display());
+            fs::remove_dir_all(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Find the version of the store currently linked in `bin_dir`.
+///
+/// # Arguments
+/// * `app` - The app to check.
+/// * `store_dir` - The store directory of the app.
+///
+/// # Returns
+/// The directory of the active version, if any.
+fn get_active_version_dir(app: &App, store_dir: &Path) -> Result<Option<PathBuf>> {
+    let bin = app.binaries().remove(0);
+    let link = get_bin_dir()?.join(bin.target());
+    let Ok(target) = fs::read_link(&link) else {
+        return Ok(None);
+    };
+    Ok(target
+        .strip_prefix(store_dir)
+        .ok()
+        .and_then(|relative| relative.components().next())
+        .map(|version| store_dir.join(version)))
+}
+
+/// Parse the expiry of a hold.
+///
+/// A date holds the app through the end of that day, in local time.
+///
+/// # Arguments
+/// * `value` - A date (`YYYY-MM-DD`) or an RFC 3339 time.
+///
+/// # Returns
+/// The expiry time, or an error if it cannot be parsed or is in the past.
+fn parse_hold_until(value: &str) -> Result<chrono::DateTime<chrono::Utc>> {
+    let until = match chrono::DateTime::parse_from_rfc3339(value) {
+        Ok(until) => until.with_timezone(&chrono::Utc),
+        Err(_) => chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| date.and_hms_opt(23, 59, 59))
+            .and_then(|end_of_day| end_of_day.and_local_timezone(chrono::Local).earliest())
+            .map(|until| until.with_timezone(&chrono::Utc))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Invalid date '{}', expected YYYY-MM-DD or an RFC 3339 time",
+                    value
+                )
+            })?,
+    };
+    if until <= chrono::Utc::now() {
+        return Err(anyhow::anyhow!("'{}' is in the past", value));
+    }
+    Ok(until)
+}
+
+/// Hold an app, so that `install` skips it.
+///
+/// The hold is stored in the local state rather than in the configuration
+/// file, which may be shared.
+///
+/// # Arguments
+/// * `app` - The app to hold.
+/// * `until` - When the hold expires, if it does.
+/// * `reason` - Why the app is held.
+fn hold_app(app: &App, until: Option<&str>, reason: Option<String>) -> Result<()> {
+    let until = until.map(parse_hold_until).transpose()?;
+    let mut state = State::load()?;
+    let entry = state.apps.entry(app.name.clone()).or_default();
+    let was_held = entry.hold.as_ref().is_some_and(|hold| hold.is_active());
+    let hold = Hold {
+        since: chrono::Utc::now(),
+        until,
+        reason,
+    };
+    say!(
+        "⏸️  {} is held {}{}",
+        app.name,
+        hold.describe(),
+        if was_held {
+            ", replacing the previous hold"
+        } else {
+            ""
+        }
+    );
+    entry.hold = Some(hold);
+    state.save()
+}
+
+/// Release the hold of an app.
+///
+/// # Arguments
+/// * `app` - The app to release.
+///
+/// # Errors
+///
+/// Returns an error if the app is not held.
+fn unhold_app(app: &App) -> Result<()> {
+    let mut state = State::load()?;
+    let hold = state
+        .apps
+        .get_mut(&app.name)
+        .and_then(|entry| entry.hold.take())
+        .ok_or_else(|| anyhow::anyhow!("{} is not held", app.name))?;
+    state.save()?;
+    if hold.is_active() {
+        say!("▶️  {} is no longer held", app.name);
+    } else {
+        say!("▶️  Removed the expired hold of {}", app.name);
+    }
+    Ok(())
+}
+
+/// Find the version to roll back to: the one following the active version
+/// in the versions of the store, listed newest first.
+fn previous_version<'a>(versions: &'a [PathBuf], active_dir: &Path) -> Option<&'a PathBuf> {
+    versions
+        .iter()
+        .skip_while(|path| *path != active_dir)
+        .nth(1)
+}
+
+/// Switch an app back to the version of the store preceding the active one.
+///
+/// # Arguments
+/// * `app` - The app to roll back.
+/// * `dry_run` - Whether to only print what would be done.
+///
+/// # Errors
+///
+/// Returns an error if the app is not installed from the store or if there is no previous version.
+fn rollback_app(app: &App, dry_run: bool) -> Result<()> {
+    if !matches!(app.installation_method(), InstallationMethod::GitHub) {
+        return Err(anyhow::anyhow!(
+            "{} is installed with custom commands and cannot be rolled back",
+            app.name
+        ));
+    }
+
+    let store_dir = get_app_store_dir(app)?;
+    let active_dir = get_active_version_dir(app, &store_dir)?
+        .ok_or_else(|| anyhow::anyhow!("{} is not installed from the version store", app.name))?;
+    let versions = list_store_versions(app, &store_dir)?;
+    let previous_dir = previous_version(&versions, &active_dir)
+        .ok_or_else(|| anyhow::anyhow!("No previous version of {} to roll back to", app.name))?;
+
+    let version_name = |path: &Path| {
+        path.file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string()
+    };
+    if dry_run {
+        say!(
+            "🔍 [DRY RUN] Would roll back {} v{} -> v{}",
+            app.name,
+            version_name(&active_dir),
+            version_name(previous_dir)
+        );
+        return Ok(());
+    }
+
+    say!(
+        "🔄 Rolling back {} v{} -> v{}",
+        app.name,
+        version_name(&active_dir),
+        version_name(previous_dir)
+    );
+    link_version(app, previous_dir)?;
+
+    // The receipt of the previous version is not kept: only record the switch
+    let mut state = State::load()?;
+    let receipt = state.apps.entry(app.name.clone()).or_default();
+    receipt.version = Some(version_name(previous_dir));
+    receipt.tag = None;
+    receipt.asset_url = None;
+    receipt.sha256 = None;
+    receipt.installed_at = Some(chrono::Utc::now());
+    state.save()?;
+    say!(
+        "✅ {} rolled back to v{}",
+        app.name,
+        version_name(previous_dir)
+    );
+
+    Ok(())
+}
+
+/// Copy a binary to the given path and make it executable.
+///
+/// # Arguments
+/// * `binary_path` - The path of the binary to copy.
+/// * `dest_path` - The destination path.
+///
+/// # Returns
+/// * `Ok(())` - If the copy was successful
+/// * `Err(Error)` - If the copy failed
+fn stage_binary(binary_path: &Path, dest_path: &Path) -> Result<()> {
+    fs::copy(binary_path, dest_path)?;
+
+    // Make executable on Unix
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(dest_path)?.permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(dest_path, perms)?;
+    }
+
+    Ok(())
+}
+
+/// Create a symbolic link to a binary at the given path.
+///
+/// # Arguments
+/// * `binary_path` - The path of the binary to link to.
+/// * `dest_path` - The path of the link.
+///
+/// # Returns
+/// * `Ok(())` - If the link was created
+/// * `Err(Error)` - If the link could not be created
+fn stage_symlink(binary_path: &Path, dest_path: &Path) -> Result<()> {
+    if dest_path.symlink_metadata().is_ok() {
+        fs::remove_file(dest_path)?;
+    }
+
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(binary_path, dest_path)?;
+    #[cfg(windows)]
+    std::os::windows::fs::symlink_file(binary_path, dest_path)?;
+
+    Ok(())
+}
+
+/// Process a template string by replacing placeholders with actual values.
+///
+/// # Arguments
+/// * `http` - The HTTP client used by download functions.
+/// * `template` - The template string to process.
+/// * `app` - The application information.
+/// * `version` - The version of the application.
+///
+/// # Returns
+/// A `Result` containing the processed template string or an error.
+///
+/// # Examples
+/// ```
+/// let template = "Hello, {{name}} {{version}} on {{os}}-{{arch}}!";
+/// ```
+async fn process_template(
+    http: &HttpClient,
+    template: &str,
+    app: &App,
+    version: &str,
+) -> Result<String> {
+    let bin_dir = get_bin_dir()?;
+    let bin_path = bin_dir.join(&app.bin);
+    let app_path = std::env::current_dir()?;
+
+    // get system info
+    let raw_os = std::env::consts::OS;
+    let raw_arch = std::env::consts::ARCH;
+
+    let (normalized_os, normalized_arch, suffix) = match (raw_os, raw_arch) {
+        ("linux", "x86_64") => ("linux", "x86_64", "x86_64-unknown-linux-gnu"),
+        ("linux", "aarch64") => ("linux", "aarch64", "aarch64-unknown-linux-gnu"),
+        ("macos", "x86_64") => ("darwin", "x86_64", "x86_64-apple-darwin"),
+        ("macos", "aarch64") => ("darwin", "aarch64", "aarch64-apple-darwin"),
+        ("windows", "x86_64") => ("windows", "x86_64", "x86_64-pc-windows-msvc"),
+        _ => {
+            return Err(anyhow::anyhow!(
+                "Unsupported platform: {}-{}",
+                raw_os,
+                raw_arch
+            ));
+        }
+    };
+
+    let mut variables = HashMap::new();
+    variables.insert("name".to_string(), app.name.clone());
+    variables.insert("bin".to_string(), app.bin.clone());
+    variables.insert("version".to_string(), version.to_string());
+    variables.insert("os".to_string(), normalized_os.to_string());
+    variables.insert("arch".to_string(), normalized_arch.to_string());
+    variables.insert("raw_os".to_string(), raw_os.to_string());
+    variables.insert("raw_arch".to_string(), raw_arch.to_string());
+    variables.insert("suffix".to_string(), suffix.to_string());
+    variables.insert("bin_dir".to_string(), bin_dir.display().to_string());
+    variables.insert("bin_path".to_string(), bin_path.display().to_string());
+    variables.insert("app_path".to_string(), app_path.display().to_string());
+
+    let mut result = template.to_string();
+
+    // Process download functions first
+    result = process_download_functions(http, &result).await?;
+
+    // Then process regular variables
+    for (key, value) in variables {
+        result = result.replace(&format!("{{{}}}", key), &value);
+    }
+
+    Ok(result)
+}
+
+/// Process download functions in the template.
 ///
 /// This function replaces all occurrences of `{download(url, dest_path)}` with the path to the downloaded file.
 ///
 /// # Arguments
 ///
+/// * `http` - The HTTP client to use.
 /// * `template` - The template string containing download functions.
 ///
 /// # Returns
 ///
 /// A `Result` containing the processed template string or an error.
-async fn process_download_functions(template: &str) -> Result<String> {
+async fn process_download_functions(http: &HttpClient, template: &str) -> Result<String> {
     let download_regex = Regex::new(r"\{download\(([^,]+),\s*([^)]+)\)\}").unwrap();
     let mut result = template.to_string();
 
@@ -832,7 +1879,7 @@ async fn process_download_functions(template: &str) -> Result<String> {
         let dest_path = cap[2].trim();
 
         // Download the file
-        let downloaded_path = download_file(url, dest_path).await?;
+        let downloaded_path = download_file(http, url, dest_path).await?;
 
         // Replace the download function with the path
         result = result.replace(full_match, &downloaded_path);
@@ -845,37 +1892,65 @@ async fn process_download_functions(template: &str) -> Result<String> {
 ///
 /// # Arguments
 ///
+/// * `http` - The HTTP client used by download functions.
 /// * `app` - The app to execute commands for.
 /// * `version` - The version of the app.
 /// * `is_update` - Whether to execute an update command.
 /// * `dry_run` - Whether to execute the command or just print it.
 ///
 async fn execute_app_commands(
+    http: &HttpClient,
     app: &App,
     version: &str,
     is_update: bool,
     dry_run: bool,
     debug: bool,
 ) -> Result<()> {
-    let (command, log) = if is_update && app.update_command.is_some() {
-        (app.update_command.as_ref().unwrap(), "update")
-    } else {
-        (app.install_command.as_ref().unwrap(), "install")
+    let (command, log) = match (&app.update_command, is_update) {
+        (Some(update_command), true) => (update_command, "update"),
+        _ => (app.install_command.as_ref().unwrap(), "install"),
     };
 
-    let processed_command = process_template(command, app, version).await?;
+    run_app_command(http, app, command, log, version, dry_run, debug).await
+}
+
+/// Run a command template for the given app.
+///
+/// # Arguments
+///
+/// * `http` - The HTTP client used by download functions.
+/// * `app` - The app to execute the command for.
+/// * `command` - The command template.
+/// * `log` - The kind of command, for messages (install, update...).
+/// * `version` - The version of the app.
+/// * `dry_run` - Whether to execute the command or just print it.
+///
+async fn run_app_command(
+    http: &HttpClient,
+    app: &App,
+    command: &str,
+    log: &str,
+    version: &str,
+    dry_run: bool,
+    debug: bool,
+) -> Result<()> {
+    let processed_command = process_template(http, command, app, version).await?;
 
     if dry_run {
-        println!(
+        say!(
             "   ⚙️ [DRY RUN] Would execute {} command for {} \n\t {} ",
-            log, app.name, processed_command
+            log,
+            app.name,
+            processed_command
         );
         return Ok(());
     }
     if debug {
-        println!(
+        say!(
             "🩺 [DEBUG] Executing {} command for {} \n{}\n🩺 [DEBUG] -- ",
-            log, app.name, processed_command
+            log,
+            app.name,
+            processed_command
         );
     }
 
@@ -896,36 +1971,518 @@ async fn execute_app_commands(
     Ok(())
 }
 
+/// Uninstall the given app.
+///
+/// Apps installed from GitHub releases have their recorded files removed (binaries,
+/// version store, man pages and completions). Apps installed with custom commands
+/// run their `uninstall_command`.
+///
+/// # Arguments
+///
+/// * `http` - The HTTP client used by download functions.
+/// * `app` - The app to uninstall.
+/// * `dry_run` - Whether to only print what would be removed.
+///
+/// # Errors
+///
+/// This function will return an error if the app cannot be uninstalled.
+async fn uninstall_app(http: &HttpClient, app: &App, dry_run: bool, debug: bool) -> Result<()> {
+    let mut state = State::load()?;
+    let receipt = state.apps.get(&app.name).cloned();
+
+    if dry_run {
+        say!("🔍 [DRY RUN] Would uninstall {}", app.name);
+    } else {
+        say!("🔄 Uninstalling {}", app.name);
+    }
+
+    match app.installation_method() {
+        InstallationMethod::GitHub => {
+            let files = match &receipt {
+                Some(receipt) if !receipt.files.is_empty() => receipt.files.clone(),
+                _ => {
+                    // Nothing recorded: fall back to the binaries and the version store
+                    let bin_dir = get_bin_dir()?;
+                    let mut files: Vec<PathBuf> = app
+                        .binaries()
+                        .iter()
+                        .map(|bin| bin_dir.join(bin.target()))
+                        .collect();
+                    files.push(get_app_store_dir(app)?);
+                    files
+                }
+            };
+            let files: Vec<PathBuf> = files
+                .into_iter()
+                .filter(|path| path.symlink_metadata().is_ok())
+                .collect();
+
+            if files.is_empty() {
+                say!("ℹ️  {} is not installed", app.name);
+            }
+            for path in files {
+                if dry_run {
+                    say!("   🗑️ [DRY RUN] Would remove {}", path.display());
+                    continue;
+                }
+                if path.is_dir() && !path.is_symlink() {
+                    fs::remove_dir_all(&path)?;
+                } else {
+                    fs::remove_file(&path)?;
+                }
+                say!("   🗑️ Removed {}", path.display());
+            }
+        }
+        InstallationMethod::Commands => {
+            let command = app.uninstall_command.as_ref().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "{} is installed with custom commands but has no uninstall_command",
+                    app.name
+                )
+            })?;
+            let version = receipt
+                .as_ref()
+                .and_then(|receipt| receipt.version.clone())
+                .unwrap_or_default();
+            run_app_command(http, app, command, "uninstall", &version, dry_run, debug).await?;
+        }
+    }
+
+    if !dry_run {
+        // Only the receipt is cleared, the hold and the last check are kept
+        if let Some(entry) = state.apps.get_mut(&app.name) {
+            *entry = AppState {
+                last_checked: entry.last_checked,
+                hold: entry.hold.take(),
+                ..AppState::default()
+            };
+            if let Some(hold) = entry.hold.as_ref().filter(|hold| hold.is_active()) {
+                say!(
+                    "⚠️  {} is still held {}, run `unhold` to release it",
+                    app.name,
+                    hold.describe()
+                );
+            }
+        }
+        state.save()?;
+        say!("✅ {} uninstalled", app.name);
+    }
+
+    Ok(())
+}
+
+/// Check whether a file extracted from an archive is an executable.
+fn is_executable(path: &Path) -> bool {
+    if path.extension().is_some_and(|ext| ext == "exe") {
+        return true;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Ok(metadata) = fs::metadata(path) {
+            return metadata.permissions().mode() & 0o111 != 0;
+        }
+    }
+    false
+}
+
+/// Find the names of the executables in an extracted archive.
+///
+/// # Arguments
+/// * `dir` - The directory to search in.
+///
+/// # Returns
+/// The sorted file names of the executables, without the `.exe` extension.
+fn find_executables(dir: &Path) -> Result<Vec<String>> {
+    let mut executables = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            executables.extend(find_executables(&path)?);
+        } else if is_executable(&path)
+            && let Some(name) = path.file_name()
+        {
+            let name = name.to_string_lossy();
+            executables.push(name.strip_suffix(".exe").unwrap_or(&name).to_string());
+        }
+    }
+    executables.sort();
+    executables.dedup();
+    Ok(executables)
+}
+
+/// Guess the main binary of a repository among the executables of its release.
+///
+/// Prefers the executable named after the repository, then the first one
+/// without an extension (scripts such as `install.sh` are less likely).
+fn guess_binary(repo_name: &str, executables: &[String]) -> Option<String> {
+    executables
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(repo_name))
+        .or_else(|| executables.iter().find(|name| !name.contains('.')))
+        .or_else(|| executables.first())
+        .cloned()
+}
+
+/// Add an app to the configuration file from a GitHub repository.
+///
+/// The latest release is downloaded for the current platform and inspected
+/// to discover the executables, and the name and description are taken from
+/// the repository metadata. The entry is appended to the configuration file,
+/// preserving its comments and ordering.
+///
+/// # Arguments
+/// * `http` - The HTTP client to use.
+/// * `config` - The loaded configuration.
+/// * `repo` - The repository, as "owner/repo".
+/// * `name` - The name of the app (defaults to the repository name).
+/// * `bin` - The binary of the app (defaults to the discovered executable).
+/// * `dry_run` - Whether to only print the proposed entry.
+///
+/// # Returns
+/// A `Result` which is `Ok` if the app was added, or an error.
+async fn add_app(
+    http: &HttpClient,
+    config: &Config,
+    repo: &str,
+    name: Option<String>,
+    bin: Option<String>,
+    dry_run: bool,
+) -> Result<()> {
+    if let Some(app) = config
+        .apps
+        .iter()
+        .find(|app| app.repo.as_deref() == Some(repo))
+    {
+        return Err(anyhow::anyhow!(
+            "{} is already configured as {}",
+            repo,
+            app.name
+        ));
+    }
+
+    check_rate_limit(http, false).await?;
+    let token = env::var("GITHUB_TOKEN").ok();
+    let repository = github::fetch_repository(http, repo, token.as_deref()).await?;
+    let release = github::fetch_latest_release(http, repo, token.as_deref()).await?;
+    say!("ℹ️  Latest release of {}: {}", repo, release.tag_name);
+
+    let bin = match bin {
+        Some(bin) => bin,
+        None => {
+            let url = get_best_url(&release)?;
+            let temp_dir = TempDir::new()?;
+            download_and_extract(http, &url, temp_dir.path(), &repository.name).await?;
+            let executables = find_executables(temp_dir.path())?;
+            if executables.len() > 1 {
+                say!("ℹ️  Executables found: {}", executables.join(", "));
+            }
+            guess_binary(&repository.name, &executables).ok_or_else(|| {
+                anyhow::anyhow!("No executable found in the release, use --bin to set it")
+            })?
+        }
+    };
+
+    let name = name.unwrap_or(repository.name);
+    if config.apps.iter().any(|app| app.name == name) {
+        return Err(anyhow::anyhow!(
+            "An app named {} is already configured, use --name to choose another name",
+            name
+        ));
+    }
+
+    let app = App {
+        name,
+        bin,
+        description: repository.description,
+        repo: Some(repo.to_string()),
+        ..App::default()
+    };
+    say!(
+        "📝 Proposed entry:\n{}",
+        config_edit::app_entry(&app, "  ")?.trim_end()
+    );
+
+    if dry_run {
+        say!("🔍 Dry run: {} not modified", config.path.display());
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(&config.path)
+        .with_context(|| format!("Failed to read config file: {}", config.path.display()))?;
+    let content = config_edit::append_app(&content, &app)?;
+    config_edit::write_config(&config.path, &content)?;
+    say!("✅ Added {} to {}", app.name, config.path.display());
+
+    Ok(())
+}
+
+/// Remove an app from the configuration file.
+///
+/// The installed files are left untouched, see `uninstall_app`.
+///
+/// # Arguments
+/// * `config` - The loaded configuration.
+/// * `app` - The app to remove.
+///
+/// # Returns
+/// A `Result` which is `Ok` if the app was removed, or an error.
+fn remove_app(config: &Config, app: &App) -> Result<()> {
+    let content = fs::read_to_string(&config.path)
+        .with_context(|| format!("Failed to read config file: {}", config.path.display()))?;
+    let content = config_edit::remove_app(&content, &app.name)?;
+    config_edit::write_config(&config.path, &content)?;
+    say!("✅ Removed {} from {}", app.name, config.path.display());
+
+    if State::load()?.is_installed(&app.name) {
+        say!(
+            "ℹ️  {} is still installed, run `uninstall {}` before removing it to delete its files",
+            app.name,
+            app.name
+        );
+    }
+
+    Ok(())
+}
+
+/// Set a field of an app in the configuration file.
+///
+/// The local state is keyed by the name of the app: an installed app cannot
+/// be renamed, and the hold and last check of other apps follow the new name.
+///
+/// # Arguments
+/// * `config` - The loaded configuration.
+/// * `app` - The app to edit.
+/// * `key` - The field to set.
+/// * `value` - The new value, parsed as YAML (`null` removes the field).
+///
+/// # Returns
+/// A `Result` which is `Ok` if the field was set, or an error.
+fn set_app_field(config: &Config, app: &App, key: &str, value: &str) -> Result<()> {
+    let content = fs::read_to_string(&config.path)
+        .with_context(|| format!("Failed to read config file: {}", config.path.display()))?;
+    let content = config_edit::set_field(&content, &app.name, key, value)?;
+
+    let mut state = State::load()?;
+    let new_name = match key {
+        "name" => Some(serde_yaml::from_str::<String>(value).unwrap_or_else(|_| value.to_string())),
+        _ => None,
+    };
+    if new_name.is_some() && state.is_installed(&app.name) {
+        return Err(anyhow::anyhow!(
+            "{} is installed, run `uninstall {}` before renaming it",
+            app.name,
+            app.name
+        ));
+    }
+
+    config_edit::write_config(&config.path, &content)?;
+    say!(
+        "✅ Set {} of {} to {} in {}",
+        key,
+        app.name,
+        value,
+        config.path.display()
+    );
+
+    if let Some(new_name) = new_name
+        && let Some(entry) = state.apps.remove(&app.name)
+    {
+        state.apps.insert(new_name, entry);
+        state.save()?;
+    }
+
+    Ok(())
+}
+
+/// Print the latest release of an app and explain the asset selection.
+///
+/// Every asset is listed with its match result against the current platform
+/// and its priority score, and the asset that `install` would download is
+/// marked with the rules that selected it.
+///
+/// # Arguments
+/// * `http` - The HTTP client to use.
+/// * `app` - The app to inspect.
+///
+/// # Returns
+/// A `Result` which is `Ok` if the release could be fetched, or an error.
+async fn info_app(http: &HttpClient, app: &App) -> Result<()> {
+    say!("📦 {}", app.name);
+    if let Some(description) = &app.description {
+        say!("   {}", description);
+    }
+    let repo = app.get_repo();
+    if repo.is_empty() {
+        return Err(anyhow::anyhow!(
+            "{} has no repo, there is no release to inspect",
+            app.name
+        ));
+    }
+
+    check_rate_limit(http, false).await?;
+    let release = fetch_app_release(http, app).await?;
+    say!("   Repository: https://github.com/{}", repo);
+    say!("   Latest tag: {}", release.tag_name);
+    say!("   Release:    {}", release.html_url);
+    if let Some(published_at) = release.published_at {
+        say!(
+            "   Published:  {}",
+            published_at.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+    }
+    say!("   Platform:   {}", github::Platform::current());
+
+    let selected = select_asset(&release);
+    let evaluations = github::evaluate_assets(&release.assets);
+    say!("\n   Assets:");
+    for evaluation in &evaluations {
+        let is_selected = selected.is_some_and(|asset| std::ptr::eq(asset, evaluation.asset));
+        let marker = if is_selected { "👉" } else { "  " };
+        match &evaluation.matched {
+            Some(_) => say!(
+                "   {} ✅ {} (score {})",
+                marker,
+                evaluation.asset.name,
+                evaluation.score
+            ),
+            None => say!("   {} ❌ {}", marker, evaluation.asset.name),
+        }
+    }
+
+    match selected {
+        Some(asset) => {
+            let evaluation = evaluations
+                .iter()
+                .find(|evaluation| std::ptr::eq(asset, evaluation.asset))
+                .unwrap();
+            say!("\n👉 Selected {}", asset.name);
+            if let Some(reason) = &evaluation.matched {
+                say!("   - matches {}: {}", github::Platform::current(), reason);
+            }
+            for (score, rule) in &evaluation.rules {
+                say!("   - {:+} {}", score, rule);
+            }
+            let candidates = evaluations
+                .iter()
+                .filter(|evaluation| evaluation.matched.is_some())
+                .count();
+            say!(
+                "   - highest score ({}) among {} matching asset(s)",
+                evaluation.score,
+                candidates
+            );
+        }
+        None => say!("\n❌ No asset matches {}", github::Platform::current()),
+    }
+
+    Ok(())
+}
+
+/// Ask the user to confirm an action on the terminal.
+///
+/// Returns `true` without asking when stdin is not a terminal or when
+/// structured output is selected, so that scripts are never blocked.
+fn confirm(question: &str) -> Result<bool> {
+    use std::io::{IsTerminal, Write};
+    if !std::io::stdin().is_terminal() || output::is_machine_output() {
+        return Ok(true);
+    }
+    print!("❓ {} [Y/n] ", question);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    let answer = answer.trim().to_lowercase();
+    Ok(answer.is_empty() || answer == "y" || answer == "yes")
+}
+
 /// Install the given app.
 ///
 /// If `dry_run` is `true`, the function will only print the installation steps without actually installing the app.
 ///
 /// # Arguments
 ///
+/// * `http` - The HTTP client to use.
+/// * `settings` - The global settings.
 /// * `app` - The app to install.
+/// * `report` - The structured record of the app, filled as the installation progresses.
+/// * `changelog` - Whether to show the release notes and ask for confirmation before an update.
 /// * `dry_run` - Whether to perform a dry run.
 ///
 /// # Errors
 ///
 /// This function will return an error if the app cannot be installed.
-async fn install_app(app: &App, dry_run: bool, debug: bool) -> Result<()> {
-    let (status, release) = get_app_status_and_release(app, debug).await?;
+async fn install_app(
+    http: &HttpClient,
+    settings: &Settings,
+    app: &App,
+    report: &mut AppReport,
+    changelog: bool,
+    dry_run: bool,
+    debug: bool,
+) -> Result<()> {
+    let state = State::load()?;
+    if let Some(hold) = state.active_hold(&app.name) {
+        say!("⏸️  Skipping {}: held {}", app.name, hold.describe());
+        // Held apps are not checked: report the version of the install receipt,
+        // or the version of the installed binary
+        report.current_version = state
+            .apps
+            .get(&app.name)
+            .and_then(|receipt| receipt.version.clone())
+            .or_else(|| {
+                app::installed_path(&app.bin).and_then(|path| {
+                    app::get_current_version_with_debug(&path.to_string_lossy(), app, debug)
+                })
+            });
+        report.hold = Some(hold.clone());
+        report.action = Some(Action::Held);
+        return Ok(());
+    }
+
+    let (status, release) = get_app_status_and_release(http, settings, app, debug).await?;
+    report.set_status(&status);
+
+    // Remember when the app was last checked
+    let mut state = State::load()?;
+    state.apps.entry(app.name.clone()).or_default().last_checked = Some(chrono::Utc::now());
+    state.save()?;
 
     if status.pixi_managed.unwrap_or(false) {
-        println!("{}", status);
+        say!("{}", status);
+        report.action = Some(Action::SkippedPixi);
         return Ok(());
     }
 
     if !status.is_version_update_needed() {
-        println!("{}", status);
+        say!("{}", status);
+        report.action = Some(Action::None);
         return Ok(());
     }
 
+    // Show what changed before updating
+    if changelog
+        && let (Some(current_version), Some(latest_version)) =
+            (&status.current_version, &status.latest_version)
+        && !app.get_repo().is_empty()
+    {
+        say!("{}", status);
+        if let Err(e) = changelog::print_changelog(http, app, current_version, latest_version).await
+        {
+            say!("⚠️  Could not fetch the release notes: {}", e);
+        }
+        if !dry_run && !confirm(&format!("Update {} to v{}?", app.name, latest_version))? {
+            say!("⏭️  Skipping {}", app.name);
+            report.action = Some(Action::Declined);
+            return Ok(());
+        }
+    }
+
     let latest_version = status.latest_version.unwrap();
     let is_update = status.current_version.is_some();
 
     if dry_run {
-        println!(
+        say!(
             "🔍 [DRY RUN] Would {} {} v{}",
             if is_update { "update" } else { "install" },
             app.name,
@@ -933,44 +2490,89 @@ async fn install_app(app: &App, dry_run: bool, debug: bool) -> Result<()> {
         );
     }
 
-    println!(
+    say!(
         "🔄 {} {} v{}",
         if is_update { "Updating" } else { "Installing" },
         app.name,
         latest_version
     );
 
-    match app.installation_method() {
+    let method = app.installation_method();
+    let receipt = match method {
         InstallationMethod::GitHub => {
             let url = get_best_url(&release)?;
+            report.asset = select_asset(&release).map(|asset| asset.name.clone());
             if dry_run {
-                println!("   📥 [DRY RUN] Would Downloading from {}", url);
-                println!(
+                say!("   📥 [DRY RUN] Would Downloading from {}", url);
+                say!(
                     "   📦 [DRY RUN] Would extract and install binary to: {}",
                     get_bin_dir()?.display()
                 );
+                say!(
+                    "   📦 [DRY RUN] Would keep the installed version in: {}",
+                    get_app_store_dir(app)?
+                        .join(store_dir_name(&latest_version))
+                        .display()
+                );
+                if app.extras {
+                    say!("   📦 [DRY RUN] Would install man pages and shell completions");
+                }
+                AppState::default()
             } else {
-                println!("   📥  Downloading from {}", url);
-                download_and_install(app, &url).await?;
+                say!("   📥  Downloading from {}", url);
+                let keep_versions = app.keep_versions.unwrap_or(settings.keep_versions);
+                download_and_install(http, app, &url, &latest_version, keep_versions).await?
             }
         }
         InstallationMethod::Commands => {
-            execute_app_commands(app, &latest_version, is_update, dry_run, debug).await?;
+            execute_app_commands(http, app, &latest_version, is_update, dry_run, debug).await?;
+            let bin_path = get_bin_dir()?.join(&app.bin);
+            AppState {
+                files: if bin_path.exists() {
+                    vec![bin_path]
+                } else {
+                    vec![]
+                },
+                ..AppState::default()
+            }
         }
+    };
+
+    // Record the install receipt
+    if !dry_run {
+        let mut state = State::load()?;
+        let entry = state.apps.entry(app.name.clone()).or_default();
+        entry.version = Some(latest_version.clone());
+        entry.tag = Some(release.tag_name.clone()).filter(|tag| !tag.is_empty());
+        entry.asset_url = receipt.asset_url;
+        entry.sha256 = receipt.sha256;
+        entry.installed_at = Some(chrono::Utc::now());
+        entry.method = Some(method);
+        entry.files = receipt.files;
+        state.save()?;
     }
 
+    report.action = Some(match (dry_run, is_update) {
+        (true, true) => Action::WouldUpdate,
+        (true, false) => Action::WouldInstall,
+        (false, true) => Action::Updated,
+        (false, false) => Action::Installed,
+    });
+
     // Verify installation
     if !dry_run {
-        if let Some(version) = app::get_current_version_with_debug(&app.bin, debug) {
-            println!("✅ {} v{} installed successfully", app.name, version);
+        if let Some(version) = app::installed_path(&app.bin).and_then(|path| {
+            app::get_current_version_with_debug(&path.to_string_lossy(), app, debug)
+        }) {
+            say!("✅ {} v{} installed successfully", app.name, version);
         } else {
-            println!(
+            say!(
                 "⚠️  {} installed but version not detectable (binary may not support standard version flags)",
                 app.name
             );
         }
     } else {
-        println!(
+        say!(
             "   ℹ️ [DRY RUN] Would check if {} installed successfully",
             app.name
         );
@@ -984,51 +2586,311 @@ async fn install_app(app: &App, dry_run: bool, debug: bool) -> Result<()> {
 /// If `dry_run` is `true`, the function will only print the installation commands without actually installing the apps.
 /// If `stop_on_error` is `true`, the function will stop installing apps if an error occurs.
 /// If `stop_on_error` is `false`, the function will continue installing apps even if an error occurs.
+/// With a structured `format`, one record per app is written to stdout.
+#[allow(clippy::too_many_arguments)]
 async fn install_apps(
+    http: &HttpClient,
+    settings: &Settings,
     apps: Vec<App>,
+    changelog: bool,
     dry_run: bool,
     stop_on_error: bool,
     debug: bool,
+    format: OutputFormat,
 ) -> Result<()> {
+    let mut reports = Vec::new();
     for app in apps {
-        let result = install_app(&app, dry_run, debug).await;
+        let mut report = AppReport::new(&app.name);
+        let result =
+            install_app(http, settings, &app, &mut report, changelog, dry_run, debug).await;
 
         if let Err(e) = result {
             eprintln!("❌ Failed to install {}: {}", app.name, e);
+            report.action = Some(Action::Failed);
+            report.error = Some(e.to_string());
+            reports.push(report);
             if stop_on_error {
+                output::print_records(format, &reports)?;
                 return Err(e);
             }
+        } else {
+            reports.push(report);
         }
     }
-    Ok(())
+    output::print_records(format, &reports)
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    // Keep stdout for structured records
+    if let Commands::Install { output, .. }
+    | Commands::Check { output, .. }
+    | Commands::List { output, .. } = &cli.command
+    {
+        output::set_output_format(*output);
+    }
+
     let config = load_config(&cli.config).await?;
+    let http = HttpClient::new(&config.settings)?;
 
     if cli.debug {
         // Check current PATH
         if let Ok(path_var) = env::var("PATH") {
-            println!("🩺 [DEBUG] Current PATH: {}", path_var);
+            say!("🩺 [DEBUG] Current PATH: {}", path_var);
         }
     }
 
     match cli.command {
-        Commands::Install { app_name, dry_run } => {
+        Commands::Install {
+            app_name,
+            dry_run,
+            output,
+            no_changelog,
+        } => {
             let apps = filter_apps(&config.apps, app_name)?;
-            install_apps(apps, dry_run, cli.stop_on_error, cli.debug).await?;
+            install_apps(
+                &http,
+                &config.settings,
+                apps,
+                !no_changelog,
+                dry_run,
+                cli.stop_on_error,
+                cli.debug,
+                output,
+            )
+            .await?;
         }
-        Commands::Check { app_name } => {
+        Commands::Check {
+            app_name,
+            output,
+            exit_code,
+            changelog,
+        } => {
             let apps = filter_apps(&config.apps, app_name)?;
-            check_apps(apps, cli.stop_on_error, cli.debug).await?;
+            let outcome = check_apps(
+                &http,
+                &config.settings,
+                apps,
+                changelog,
+                cli.stop_on_error,
+                cli.debug,
+                output,
+            )
+            .await;
+            match outcome {
+                Ok(outcome) if exit_code => std::process::exit(outcome.exit_code()),
+                Ok(_) => {}
+                Err(e) if exit_code => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(CheckOutcome::from_error(&e).exit_code());
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Commands::List {
+            installed,
+            missing,
+            sort,
+            output,
+        } => {
+            list_apps(config.apps, installed, missing, sort, output)?;
+        }
+        Commands::Uninstall { app_name, dry_run } => {
+            let apps = filter_apps(&config.apps, Some(app_name))?;
+            uninstall_app(&http, &apps[0], dry_run, cli.debug).await?;
+        }
+        Commands::Doctor => {
+            doctor::run(&http, &config.apps).await?;
+        }
+        Commands::Info { app_name } => {
+            let apps = filter_apps(&config.apps, Some(app_name))?;
+            info_app(&http, &apps[0]).await?;
+        }
+        Commands::Add {
+            repo,
+            name,
+            bin,
+            dry_run,
+        } => {
+            add_app(&http, &config, &repo, name, bin, dry_run).await?;
+        }
+        Commands::Remove { app_name } => {
+            let apps = filter_apps(&config.apps, Some(app_name))?;
+            remove_app(&config, &apps[0])?;
+        }
+        Commands::Set {
+            app_name,
+            key,
+            value,
+        } => {
+            let apps = filter_apps(&config.apps, Some(app_name))?;
+            set_app_field(&config, &apps[0], &key, &value)?;
+        }
+        Commands::Rollback { app_name, dry_run } => {
+            let apps = filter_apps(&config.apps, Some(app_name))?;
+            rollback_app(&apps[0], dry_run)?;
+        }
+        Commands::Hold {
+            app_name,
+            until,
+            reason,
+        } => {
+            let apps = filter_apps(&config.apps, Some(app_name))?;
+            hold_app(&apps[0], until.as_deref(), reason)?;
+        }
+        Commands::Unhold { app_name } => {
+            let apps = filter_apps(&config.apps, Some(app_name))?;
+            unhold_app(&apps[0])?;
         }
         Commands::SelfUpdate { dry_run } => {
-            self_update(dry_run).await?;
+            self_update(&http, dry_run).await?;
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag: &str) -> Release {
+        Release {
+            tag_name: tag.to_string(),
+            ..Release::default()
+        }
+    }
+
+    #[test]
+    fn highest_allowed_release_picks_the_highest_version() {
+        let app = App::default();
+        let releases = vec![release("v2.0.0"), release("v1.2.9"), release("v1.3.0")];
+        let allowed = highest_allowed_release(releases, &app, UpdatePolicy::Minor, "1.2.3");
+        assert_eq!(
+            allowed.map(|(_, version)| version).as_deref(),
+            Some("1.3.0")
+        );
+    }
+
+    #[test]
+    fn highest_allowed_release_skips_prereleases_and_older_versions() {
+        let app = App::default();
+        let mut prerelease = release("v1.2.5");
+        prerelease.prerelease = true;
+        let releases = vec![release("v2.0.0"), prerelease, release("v1.2.2")];
+        assert!(highest_allowed_release(releases, &app, UpdatePolicy::Patch, "1.2.3").is_none());
+        let releases = vec![release("v1.2.4"), release("v1.2.3")];
+        let allowed = highest_allowed_release(releases, &app, UpdatePolicy::Patch, "1.2.3");
+        assert_eq!(
+            allowed.map(|(_, version)| version).as_deref(),
+            Some("1.2.4")
+        );
+    }
+
+    #[test]
+    fn parse_hold_until_holds_through_the_end_of_the_day() {
+        let until = parse_hold_until("2999-01-31").unwrap();
+        let local = until.with_timezone(&chrono::Local);
+        assert_eq!(
+            local.naive_local(),
+            chrono::NaiveDate::from_ymd_opt(2999, 1, 31)
+                .unwrap()
+                .and_hms_opt(23, 59, 59)
+                .unwrap()
+        );
+        assert_eq!(
+            parse_hold_until("2999-01-31T12:00:00+02:00").unwrap(),
+            chrono::DateTime::parse_from_rfc3339("2999-01-31T10:00:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_hold_until_rejects_past_and_invalid_dates() {
+        assert!(parse_hold_until("2000-01-01").is_err());
+        assert!(parse_hold_until("2000-01-01T00:00:00Z").is_err());
+        let yesterday = chrono::Local::now().date_naive() - chrono::Days::new(1);
+        assert!(parse_hold_until(&yesterday.format("%Y-%m-%d").to_string()).is_err());
+        assert!(parse_hold_until("tomorrow").is_err());
+        assert!(parse_hold_until("2999-02-30").is_err());
+    }
+
+    /// Create version directories in a store, the first one installed first.
+    fn store(versions: &[&str]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let start = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+        for (i, version) in versions.iter().enumerate() {
+            let path = dir.path().join(version);
+            fs::create_dir(&path).unwrap();
+            fs::File::open(&path)
+                .unwrap()
+                .set_modified(start + std::time::Duration::from_secs(i as u64))
+                .unwrap();
+        }
+        dir
+    }
+
+    fn names(versions: &[PathBuf]) -> Vec<String> {
+        versions
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn store_versions_are_ordered_by_scheme() {
+        // Installed out of order, with a name not following the scheme and a temporary directory
+        let dir = store(&["1.10.0", "local", "1.2.0", "1.9.1", ".download123"]);
+        let versions = list_store_versions(&App::default(), dir.path()).unwrap();
+        assert_eq!(names(&versions), ["1.10.0", "1.9.1", "1.2.0", "local"]);
+    }
+
+    #[test]
+    fn store_versions_fall_back_to_installation_time() {
+        let dir = store(&["nightly-b", "nightly-a", "nightly-c"]);
+        let app = App {
+            version_scheme: VersionScheme::String,
+            ..App::default()
+        };
+        let versions = list_store_versions(&app, dir.path()).unwrap();
+        assert_eq!(names(&versions), ["nightly-c", "nightly-a", "nightly-b"]);
+    }
+
+    #[test]
+    fn prune_keeps_the_active_version() {
+        let dir = store(&["1.0.0", "1.1.0", "1.2.0", "2.0.0"]);
+        let app = App::default();
+        // The active version is not the newest one, after a rollback
+        prune_versions(&app, dir.path(), &dir.path().join("1.1.0"), 2).unwrap();
+        let versions = list_store_versions(&app, dir.path()).unwrap();
+        assert_eq!(names(&versions), ["2.0.0", "1.1.0"]);
+        prune_versions(&app, dir.path(), &dir.path().join("1.1.0"), 0).unwrap();
+        let versions = list_store_versions(&app, dir.path()).unwrap();
+        assert_eq!(names(&versions), ["1.1.0"]);
+    }
+
+    #[test]
+    fn rollback_targets_the_previous_version() {
+        let dir = store(&["1.2.0", "1.10.0", "1.9.1"]);
+        let versions = list_store_versions(&App::default(), dir.path()).unwrap();
+        let previous = |active: &str| {
+            previous_version(&versions, &dir.path().join(active))
+                .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+        };
+        assert_eq!(previous("1.10.0").as_deref(), Some("1.9.1"));
+        assert_eq!(previous("1.9.1").as_deref(), Some("1.2.0"));
+        assert_eq!(previous("1.2.0"), None);
+        assert_eq!(previous("2.0.0"), None);
+    }
+
+    #[test]
+    fn store_dir_names_have_no_separators() {
+        assert_eq!(store_dir_name("1.2.3"), "1.2.3");
+        assert_eq!(store_dir_name("release/2024-05-01"), "release_2024-05-01");
+        assert_eq!(store_dir_name("a\\b"), "a_b");
+        assert_eq!(store_dir_name(".hidden"), "_hidden");
+        assert_eq!(store_dir_name(".."), "_.");
+        assert_eq!(store_dir_name(""), "_");
+    }
+}
@@ -0,0 +1,117 @@
+use crate::app::AppStatus;
+use crate::state::Hold;
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether structured output is written to stdout.
+static MACHINE_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Print a human-readable message.
+///
+/// Messages go to stdout, or to stderr when a structured output format is
+/// selected so that stdout stays parseable.
+#[macro_export]
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if $crate::output::is_machine_output() {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// Output formats of the `check`, `install` and `list` commands.
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable messages
+    #[default]
+    Text,
+    /// JSON records on stdout
+    Json,
+    /// YAML records on stdout
+    Yaml,
+}
+
+/// Select the output format for the whole run.
+pub fn set_output_format(format: OutputFormat) {
+    MACHINE_OUTPUT.store(format != OutputFormat::Text, Ordering::Relaxed);
+}
+
+pub fn is_machine_output() -> bool {
+    MACHINE_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Write structured records to stdout in the given format.
+///
+/// Nothing is written for the text format.
+pub fn print_records<T: Serialize>(format: OutputFormat, records: &T) -> Result<()> {
+    match format {
+        OutputFormat::Text => {}
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(records)?),
+        OutputFormat::Yaml => print!("{}", serde_yaml::to_string(records)?),
+    }
+    Ok(())
+}
+
+/// Action taken on an app by `install`.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Already at the latest version
+    None,
+    /// Managed by pixi, left untouched
+    SkippedPixi,
+    Installed,
+    Updated,
+    WouldInstall,
+    WouldUpdate,
+    /// Update declined after reading the release notes
+    Declined,
+    /// Held with the `hold` command, left untouched
+    Held,
+    Failed,
+}
+
+/// Structured record of the status of an app.
+#[derive(Debug, Default, Serialize)]
+pub struct AppReport {
+    pub name: String,
+    pub current_version: Option<String>,
+    pub latest_version: Option<String>,
+    pub update_needed: bool,
+    /// The installed version is newer than the latest release
+    pub newer_than_latest: bool,
+    /// A newer version not applied because of the update policy
+    pub held_back_version: Option<String>,
+    /// Hold set with the `hold` command
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hold: Option<Hold>,
+    pub pixi_managed: bool,
+    /// Name of the release asset selected for the current platform
+    pub asset: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<Action>,
+    pub error: Option<String>,
+}
+
+impl AppReport {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Fill the version information from the status of the app.
+    pub fn set_status(&mut self, status: &AppStatus) {
+        self.current_version = status.current_version.clone();
+        self.latest_version = status.latest_version.clone();
+        self.update_needed = !status.is_pixi_managed() && status.is_version_update_needed();
+        self.newer_than_latest = status.is_newer_than_latest();
+        self.held_back_version = status.held_back_version.clone();
+        self.pixi_managed = status.is_pixi_managed();
+    }
+}
@@ -0,0 +1,56 @@
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::io::IsTerminal;
+use std::sync::OnceLock;
+
+static MULTI_PROGRESS: OnceLock<MultiProgress> = OnceLock::new();
+
+/// Get the shared progress container.
+///
+/// All download bars are attached to the same container so that concurrent
+/// downloads are stacked one bar per app instead of overwriting each other.
+/// Drawing is disabled when stdout is not a terminal.
+fn multi_progress() -> &'static MultiProgress {
+    MULTI_PROGRESS.get_or_init(|| {
+        if std::io::stdout().is_terminal() {
+            MultiProgress::new()
+        } else {
+            MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+        }
+    })
+}
+
+/// Create a progress bar for a download.
+///
+/// # Arguments
+/// * `label` - The label shown in front of the bar (usually the app name).
+/// * `total` - The expected size in bytes, if announced by the server.
+///
+/// # Returns
+/// A progress bar showing bytes, rate and ETA, or a spinner when the size is unknown.
+pub fn download_bar(label: &str, total: Option<u64>) -> ProgressBar {
+    let bar = match total {
+        Some(total) => {
+            let bar = ProgressBar::new(total);
+            bar.set_style(
+                ProgressStyle::with_template(
+                    "   {prefix:.bold} [{bar:30.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec}, ETA {eta})",
+                )
+                .unwrap()
+                .progress_chars("=> "),
+            );
+            bar
+        }
+        None => {
+            let bar = ProgressBar::new_spinner();
+            bar.set_style(
+                ProgressStyle::with_template(
+                    "   {prefix:.bold} {spinner} {bytes} ({bytes_per_sec})",
+                )
+                .unwrap(),
+            );
+            bar
+        }
+    };
+    bar.set_prefix(label.to_string());
+    multi_progress().add(bar)
+}
@@ -0,0 +1,176 @@
+use crate::app::InstallationMethod;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Local state persisted between runs.
+///
+/// Stored as JSON in `~/.local/share/rs-gh-app/state.json` (or under `$XDG_DATA_HOME`).
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct State {
+    #[serde(default)]
+    pub apps: BTreeMap<String, AppState>,
+}
+
+/// Install receipt recorded for an installed app.
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+pub struct AppState {
+    /// Installed version
+    pub version: Option<String>,
+    /// Release tag the version was installed from
+    pub tag: Option<String>,
+    /// URL of the downloaded release asset
+    pub asset_url: Option<String>,
+    /// SHA-256 of the downloaded release asset
+    pub sha256: Option<String>,
+    /// Time of the installation
+    pub installed_at: Option<DateTime<Utc>>,
+    /// Installation method used
+    pub method: Option<InstallationMethod>,
+    /// Files written when installing the app
+    #[serde(default)]
+    pub files: Vec<PathBuf>,
+    /// Last time the latest version was checked
+    pub last_checked: Option<DateTime<Utc>>,
+    /// Hold set with the `hold` command, freezing the app
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hold: Option<Hold>,
+}
+
+/// A hold keeping an app at its installed version.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Hold {
+    /// Time the hold was set
+    pub since: DateTime<Utc>,
+    /// Time the hold expires, if any
+    pub until: Option<DateTime<Utc>>,
+    /// Why the app is held
+    pub reason: Option<String>,
+}
+
+impl Hold {
+    /// Check whether the hold is still in effect.
+    pub fn is_active(&self) -> bool {
+        self.until.is_none_or(|until| Utc::now() < until)
+    }
+
+    /// Describe the expiry and reason of the hold.
+    pub fn describe(&self) -> String {
+        let until = match self.until {
+            Some(until) => format!(
+                "until {}",
+                until.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+            ),
+            None => "until unheld".to_string(),
+        };
+        match &self.reason {
+            Some(reason) => format!("{} ({})", until, reason),
+            None => until,
+        }
+    }
+}
+
+impl State {
+    /// Get the path of the state file.
+    pub fn path() -> Result<PathBuf> {
+        Ok(crate::get_data_home()?.join("rs-gh-app").join("state.json"))
+    }
+
+    /// Load the state, or an empty state if nothing was recorded yet.
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read state file: {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse state file: {}", path.display()))
+    }
+
+    /// Check whether an app has an install receipt.
+    ///
+    /// `check`, `install` and `hold` also record apps that are not installed.
+    pub fn is_installed(&self, name: &str) -> bool {
+        self.apps
+            .get(name)
+            .is_some_and(|app| app.version.is_some() || !app.files.is_empty())
+    }
+
+    /// Get the hold of an app, if it is still in effect.
+    pub fn active_hold(&self, name: &str) -> Option<&Hold> {
+        self.apps
+            .get(name)
+            .and_then(|app| app.hold.as_ref())
+            .filter(|hold| hold.is_active())
+    }
+
+    /// Write the state to disk.
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // write to a temporary file first so that an interrupted write never corrupts the state
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp_path, &path)
+            .with_context(|| format!("Failed to write state file: {}", path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hold(until: Option<DateTime<Utc>>) -> Hold {
+        Hold {
+            since: Utc::now(),
+            until,
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn hold_is_active_until_it_expires() {
+        assert!(hold(None).is_active());
+        assert!(hold(Some(Utc::now() + chrono::Duration::hours(1))).is_active());
+        assert!(!hold(Some(Utc::now() - chrono::Duration::seconds(1))).is_active());
+    }
+
+    #[test]
+    fn active_hold_ignores_expired_holds() {
+        let mut state = State::default();
+        state.apps.insert(
+            "bat".to_string(),
+            AppState {
+                hold: Some(hold(Some(Utc::now() - chrono::Duration::hours(1)))),
+                ..AppState::default()
+            },
+        );
+        state.apps.insert(
+            "dust".to_string(),
+            AppState {
+                hold: Some(hold(None)),
+                ..AppState::default()
+            },
+        );
+        assert!(state.active_hold("bat").is_none());
+        assert!(state.active_hold("dust").is_some());
+        assert!(state.active_hold("uv").is_none());
+        // Checked or held apps are not installed
+        assert!(!state.is_installed("bat"));
+        state.apps.insert(
+            "uv".to_string(),
+            AppState {
+                version: Some("0.4.0".to_string()),
+                ..AppState::default()
+            },
+        );
+        assert!(state.is_installed("uv"));
+    }
+}
@@ -0,0 +1,425 @@
+use crate::app::{extract_version_from_string, normalize_version};
+use chrono::NaiveDate;
+use regex::Regex;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::LazyLock;
+
+/// A calendar version in a string, with an optional modifier.
+static CALVER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\d+(?:[._-]\d+)+(?:[._-]?[A-Za-z][0-9A-Za-z]*)?").unwrap());
+
+/// A whole calendar version, capturing its numbers and its modifier.
+static CALVER_PARTS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[vV]?(\d+(?:[._-]\d+)*)(?:[._-]?([A-Za-z][0-9A-Za-z]*))?$").unwrap()
+});
+
+/// A version with any number of numeric components.
+static NUMERIC: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\d+(?:\.\d+)+").unwrap());
+
+/// A date, the separators being checked to be the same.
+static DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d{4})([-._]?)(\d{2})([-._]?)(\d{2})").unwrap());
+
+/// How the versions of an app are parsed and ordered.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionScheme {
+    /// Semantic versions: `1.2.3`, `1.0.0-beta.2` (`v1.7` is read as `1.7.0`)
+    #[default]
+    Semver,
+    /// Calendar versions: `2024.10.3`, `24.04`, with an optional modifier (`2024.10.3-rc1`)
+    Calver,
+    /// Any number of numeric components: `1.2.3.4`, `10.0.19041.1`
+    Numeric,
+    /// Dates: `2024-05-01`, `2024.05.01` or `20240501`
+    Date,
+    /// Opaque strings such as `nightly-abc123`: any different version is an update
+    String,
+}
+
+/// Which updates of an app are applied.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdatePolicy {
+    /// Only patch updates (`1.2.3` -> `1.2.4`)
+    Patch,
+    /// Patch and minor updates (`1.2.3` -> `1.3.0`)
+    Minor,
+    /// Every update
+    #[default]
+    Major,
+    /// No update
+    None,
+}
+
+/// Size of the change between two versions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl UpdatePolicy {
+    /// Check whether an update is allowed by the policy.
+    ///
+    /// # Arguments
+    /// * `scheme` - The version scheme of the app.
+    /// * `current` - The installed version.
+    /// * `latest` - The version to update to.
+    ///
+    /// # Returns
+    /// `true` if the update may be applied.
+    pub fn allows(&self, scheme: VersionScheme, current: &str, latest: &str) -> bool {
+        match self {
+            UpdatePolicy::Major => true,
+            UpdatePolicy::Minor => scheme.bump(current, latest) != Bump::Major,
+            UpdatePolicy::Patch => scheme.bump(current, latest) == Bump::Patch,
+            UpdatePolicy::None => false,
+        }
+    }
+}
+
+impl fmt::Display for UpdatePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UpdatePolicy::Patch => write!(f, "patch"),
+            UpdatePolicy::Minor => write!(f, "minor"),
+            UpdatePolicy::Major => write!(f, "major"),
+            UpdatePolicy::None => write!(f, "none"),
+        }
+    }
+}
+
+impl VersionScheme {
+    pub fn is_semver(&self) -> bool {
+        *self == VersionScheme::Semver
+    }
+
+    /// Get the numeric components of a version, when the scheme has some.
+    fn components(&self, version: &str) -> Option<Vec<u64>> {
+        match self {
+            VersionScheme::Semver => {
+                let version = Version::parse(version).ok()?;
+                Some(vec![version.major, version.minor, version.patch])
+            }
+            VersionScheme::Calver => split_calver(version).map(|(numbers, _)| numbers),
+            VersionScheme::Numeric => parse_numbers(version, &['.']),
+            VersionScheme::Date | VersionScheme::String => None,
+        }
+    }
+
+    /// Get the size of the change from `current` to `latest`.
+    ///
+    /// As with Cargo, a change up to the first non-zero component of `current`
+    /// is major, so `0.18.1` -> `0.19.0` is a major change while `0.18.1` ->
+    /// `0.18.2` is a patch. Versions without numeric components always make
+    /// a major change.
+    fn bump(&self, current: &str, latest: &str) -> Bump {
+        let (Some(current), Some(latest)) = (self.components(current), self.components(latest))
+        else {
+            return Bump::Major;
+        };
+        let Some(major) = current.iter().position(|&n| n != 0) else {
+            return Bump::Major;
+        };
+        let len = current.len().max(latest.len());
+        let changed = (0..len)
+            .find(|&i| current.get(i).copied().unwrap_or(0) != latest.get(i).copied().unwrap_or(0));
+        match changed {
+            None => Bump::Patch,
+            Some(i) if i <= major => Bump::Major,
+            Some(1) => Bump::Minor,
+            Some(_) => Bump::Patch,
+        }
+    }
+
+    /// Find a version of this scheme in a string, such as a tag or the output of a probe.
+    ///
+    /// # Arguments
+    /// * `s` - The string to search.
+    ///
+    /// # Returns
+    /// The first version found, or None. For `string`, the version is the
+    /// first word with a digit of the first line (or its first word), so that a
+    /// tag is kept whole and `tool nightly-abc123` gives `nightly-abc123`.
+    pub fn extract(&self, s: &str) -> Option<String> {
+        match self {
+            VersionScheme::Semver => extract_version_from_string(s),
+            VersionScheme::Calver => CALVER.find(s).map(|m| m.as_str().to_string()),
+            VersionScheme::Numeric => NUMERIC.find(s).map(|m| m.as_str().to_string()),
+            VersionScheme::Date => parse_date(s).map(|date| date.format("%Y-%m-%d").to_string()),
+            VersionScheme::String => {
+                // The first word with a digit on the first line, which skips the name
+                // of the program, or the first word if none has a digit
+                let line = s.lines().map(str::trim).find(|line| !line.is_empty())?;
+                let mut words = line.split_whitespace();
+                let first = words.clone().next()?;
+                Some(
+                    words
+                        .find(|word| word.contains(|c: char| c.is_ascii_digit()))
+                        .unwrap_or(first)
+                        .to_string(),
+                )
+            }
+        }
+    }
+
+    /// Normalise a version extracted with a custom regex.
+    ///
+    /// Semantic versions are normalised with `normalize_version`, other schemes
+    /// keep the version as it is (trimmed).
+    pub fn normalize(&self, version: &str) -> String {
+        match self {
+            VersionScheme::Semver => normalize_version(version),
+            VersionScheme::Date => self
+                .extract(version)
+                .unwrap_or_else(|| version.trim().to_string()),
+            _ => version.trim().to_string(),
+        }
+    }
+
+    /// Compare two versions of this scheme.
+    ///
+    /// # Arguments
+    /// * `a` - The first version.
+    /// * `b` - The second version.
+    ///
+    /// # Returns
+    /// The ordering of `a` relative to `b`, or None if they cannot be ordered
+    /// (a version does not follow the scheme, or different `string` versions).
+    pub fn compare(&self, a: &str, b: &str) -> Option<Ordering> {
+        match self {
+            VersionScheme::Semver => Some(Version::parse(a).ok()?.cmp(&Version::parse(b).ok()?)),
+            VersionScheme::Calver => {
+                let (a_numbers, a_modifier) = split_calver(a)?;
+                let (b_numbers, b_modifier) = split_calver(b)?;
+                Some(compare_numbers(&a_numbers, &b_numbers).then_with(|| {
+                    // A modifier marks a prerelease, ranked before the release itself
+                    match (a_modifier, b_modifier) {
+                        (None, None) => Ordering::Equal,
+                        (None, Some(_)) => Ordering::Greater,
+                        (Some(_), None) => Ordering::Less,
+                        (Some(a), Some(b)) => a.cmp(b),
+                    }
+                }))
+            }
+            VersionScheme::Numeric => Some(compare_numbers(
+                &parse_numbers(a, &['.'])?,
+                &parse_numbers(b, &['.'])?,
+            )),
+            VersionScheme::Date => Some(parse_date(a)?.cmp(&parse_date(b)?)),
+            VersionScheme::String => (a == b).then_some(Ordering::Equal),
+        }
+    }
+}
+
+/// Parse the numeric components of a version, ignoring a leading `v`.
+fn parse_numbers(version: &str, separators: &[char]) -> Option<Vec<u64>> {
+    let version = version.trim();
+    let version = version.strip_prefix(['v', 'V']).unwrap_or(version);
+    version
+        .split(separators)
+        .map(|part| part.parse().ok())
+        .collect()
+}
+
+/// Compare numeric components, missing components counting as zero.
+fn compare_numbers(a: &[u64], b: &[u64]) -> Ordering {
+    (0..a.len().max(b.len()))
+        .map(|i| {
+            let a = a.get(i).copied().unwrap_or(0);
+            let b = b.get(i).copied().unwrap_or(0);
+            a.cmp(&b)
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Split a calendar version into its numeric components and its modifier.
+fn split_calver(version: &str) -> Option<(Vec<u64>, Option<&str>)> {
+    let caps = CALVER_PARTS.captures(version.trim())?;
+    let numbers = parse_numbers(caps.get(1)?.as_str(), &['.', '-', '_'])?;
+    Some((numbers, caps.get(2).map(|m| m.as_str())))
+}
+
+/// Find a date in a version, as `YYYY-MM-DD`, `YYYY.MM.DD`, `YYYY_MM_DD` or `YYYYMMDD`.
+fn parse_date(s: &str) -> Option<NaiveDate> {
+    DATE.captures_iter(s)
+        .filter(|caps| caps[2] == caps[4])
+        .find_map(|caps| {
+            NaiveDate::from_ymd_opt(
+                caps[1].parse().ok()?,
+                caps[3].parse().ok()?,
+                caps[5].parse().ok()?,
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bump() {
+        let semver = VersionScheme::Semver;
+        assert_eq!(semver.bump("1.2.3", "1.2.4"), Bump::Patch);
+        assert_eq!(semver.bump("1.2.3", "1.3.0"), Bump::Minor);
+        assert_eq!(semver.bump("1.2.3", "2.0.0"), Bump::Major);
+        assert_eq!(semver.bump("0.18.1", "0.19.0"), Bump::Major);
+        assert_eq!(semver.bump("0.18.1", "0.18.2"), Bump::Patch);
+        assert_eq!(semver.bump("0.0.3", "0.0.4"), Bump::Major);
+        assert_eq!(semver.bump("0.0.0", "0.0.1"), Bump::Major);
+
+        // Missing components count as zero
+        let calver = VersionScheme::Calver;
+        assert_eq!(calver.bump("24.04", "24.04.1"), Bump::Patch);
+        assert_eq!(calver.bump("24.04", "24.10"), Bump::Minor);
+        assert_eq!(calver.bump("24.10.1", "25.04"), Bump::Major);
+        let numeric = VersionScheme::Numeric;
+        assert_eq!(numeric.bump("1.2", "1.2.0.1"), Bump::Patch);
+        assert_eq!(numeric.bump("10.0.19041.1", "10.0.19041.2"), Bump::Patch);
+        assert_eq!(numeric.bump("10.0.19041.1", "10.1"), Bump::Minor);
+
+        // Versions without numbers
+        assert_eq!(
+            VersionScheme::Date.bump("2024-05-01", "2024-05-02"),
+            Bump::Major
+        );
+        assert_eq!(VersionScheme::String.bump("a", "b"), Bump::Major);
+    }
+
+    #[test]
+    fn allows() {
+        let semver = VersionScheme::Semver;
+        assert!(UpdatePolicy::Major.allows(semver, "1.2.3", "2.0.0"));
+        assert!(UpdatePolicy::Minor.allows(semver, "1.2.3", "1.3.0"));
+        assert!(!UpdatePolicy::Minor.allows(semver, "1.2.3", "2.0.0"));
+        assert!(UpdatePolicy::Patch.allows(semver, "1.2.3", "1.2.9"));
+        assert!(!UpdatePolicy::Patch.allows(semver, "1.2.3", "1.3.0"));
+        assert!(!UpdatePolicy::None.allows(semver, "1.2.3", "1.2.4"));
+        assert!(!UpdatePolicy::Minor.allows(semver, "0.18.1", "0.19.0"));
+        assert!(UpdatePolicy::Patch.allows(semver, "0.18.1", "0.18.2"));
+        assert!(!UpdatePolicy::Patch.allows(semver, "0.0.3", "0.0.4"));
+        assert!(UpdatePolicy::Patch.allows(VersionScheme::Calver, "24.04", "24.04.1"));
+        assert!(!UpdatePolicy::Minor.allows(VersionScheme::String, "a", "b"));
+    }
+
+    #[test]
+    fn semver() {
+        let scheme = VersionScheme::Semver;
+        assert_eq!(scheme.extract("tool v1.7").as_deref(), Some("1.7.0"));
+        assert_eq!(
+            scheme.compare("1.0.0-beta.2", "1.0.0"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(scheme.compare("1.10.0", "1.9.0"), Some(Ordering::Greater));
+        assert_eq!(scheme.compare("1.2.3.4", "1.2.3"), None);
+    }
+
+    #[test]
+    fn calver() {
+        let scheme = VersionScheme::Calver;
+        assert_eq!(
+            scheme.extract("tool 2024.10.3-rc1 (2024-10-03)").as_deref(),
+            Some("2024.10.3-rc1")
+        );
+        assert_eq!(scheme.extract("v24.04").as_deref(), Some("24.04"));
+        assert_eq!(
+            scheme.compare("2024.10.3", "2024.9.12"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(scheme.compare("24.04", "24.04.1"), Some(Ordering::Less));
+        assert_eq!(
+            scheme.compare("2024.10", "2024.10.0"),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(
+            scheme.compare("2024.11", "2024.10.5"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            scheme.compare("2024.10.3-rc1", "2024.10.3"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            scheme.compare("2024.10.3rc2", "2024.10.3-rc1"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(scheme.compare("latest", "2024.10"), None);
+    }
+
+    #[test]
+    fn numeric() {
+        let scheme = VersionScheme::Numeric;
+        assert_eq!(
+            scheme.extract("build 10.0.19041.1").as_deref(),
+            Some("10.0.19041.1")
+        );
+        assert_eq!(
+            scheme.compare("10.0.19041.1", "10.0.9200.16384"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(scheme.compare("1.2", "1.2.0.0"), Some(Ordering::Equal));
+        assert_eq!(scheme.compare("v1.2.3", "1.2.4"), Some(Ordering::Less));
+        assert_eq!(scheme.compare("1.2-beta", "1.2"), None);
+    }
+
+    #[test]
+    fn date() {
+        let scheme = VersionScheme::Date;
+        for s in [
+            "2024-05-01",
+            "release 2024.05.01",
+            "20240501",
+            "nightly_2024_05_01",
+        ] {
+            assert_eq!(scheme.extract(s).as_deref(), Some("2024-05-01"), "{}", s);
+        }
+        assert_eq!(scheme.extract("2024-05.01"), None);
+        assert_eq!(scheme.extract("2024-13-01"), None);
+        assert_eq!(
+            scheme.compare("2024-05-01", "20240502"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            scheme.compare("2025.01.01", "2024-12-31"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(scheme.normalize("20240501"), "2024-05-01");
+    }
+
+    #[test]
+    fn string() {
+        let scheme = VersionScheme::String;
+        assert_eq!(
+            scheme.extract("nightly-abc123").as_deref(),
+            Some("nightly-abc123")
+        );
+        assert_eq!(
+            scheme.extract("  nightly-abc123\n").as_deref(),
+            Some("nightly-abc123")
+        );
+        assert_eq!(scheme.extract("  "), None);
+        assert_eq!(scheme.extract("latest").as_deref(), Some("latest"));
+        // Multi-line version output
+        assert_eq!(
+            scheme
+                .extract("\ntool nightly-abc123\nbuilt 2024-05-01 with rustc 1.80\n")
+                .as_deref(),
+            Some("nightly-abc123")
+        );
+        assert_eq!(
+            scheme.extract("edge\ncommit 1a2b3c\n").as_deref(),
+            Some("edge")
+        );
+        assert_eq!(
+            scheme.compare("nightly-abc123", "nightly-abc123"),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(scheme.compare("nightly-abc123", "nightly-def456"), None);
+    }
+}